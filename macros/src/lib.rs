@@ -0,0 +1,142 @@
+//! The `hid_descriptor!` macro, which writes a report descriptor from the item names
+//! that `HidItem` prints.
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+use usb_hid_descriptor_parser::hid::assembler;
+
+/// Writes a report descriptor as a `[u8; N]` array, from items as `HidItem` prints them.
+///
+/// The items are read by `hid::assembler::assemble`, so values are names, as in
+/// `Usage Page (Telephony Page)`, or numbers. Extended usages name their usage page, as in
+/// `Usage (Consumer Page: Volume Increment)`. Input, Output and Feature items list their flags,
+/// unlisted flags are left 0. The `[Global]` style item types after an item are optional.
+/// Names the Rust tokenizer can't read, such as `Mother's Day`, can be quoted.
+///
+/// A `const NAME;` header, with an optional visibility, declares a constant instead.
+///
+/// ```
+/// use usb_hid_descriptor_macros::hid_descriptor;
+///
+/// hid_descriptor! {
+///     pub const HEADSET;
+///
+///     Usage Page (Telephony Page)
+///     Usage (Headset)
+///     Collection (Application)
+///         Logical Minimum (0)
+///         Logical Maximum (1)
+///         Usage (Hook Switch)
+///         Report Size (1)
+///         Report Count (1)
+///         Input (Data, Variable, Absolute)
+///     End Collection
+/// }
+///
+/// assert_eq!(HEADSET.len(), 19);
+/// ```
+#[proc_macro]
+pub fn hid_descriptor(input: TokenStream) -> TokenStream {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+
+    let (header, items) = match parse_header(&tokens) {
+        Ok(header) => header,
+        Err((span, message)) => return compile_error(span, &message),
+    };
+    let bytes = match assemble(items) {
+        Ok(bytes) => bytes,
+        Err((span, message)) => return compile_error(span, &message),
+    };
+
+    let array = format!(
+        "[{}]",
+        bytes.iter().map(|byte| format!("{:#04X}u8", byte)).collect::<Vec<String>>().join(", ")
+    );
+    let output = match header {
+        Some((visibility, name)) => format!("{} const {}: [u8; {}] = {};", visibility, name, bytes.len(), array),
+        None => array,
+    };
+
+    output.parse().unwrap()
+}
+
+type MacroError = (Span, String);
+
+// The visibility and name of the constant
+type Header = Option<(String, Ident)>;
+
+// Splits off the `[visibility] const NAME;` header, if there is one
+fn parse_header(tokens: &[TokenTree]) -> Result<(Header, &[TokenTree]), MacroError> {
+    let const_index = tokens
+        .iter()
+        .take(3)
+        .position(|token| matches!(token, TokenTree::Ident(ident) if ident.to_string() == "const"));
+    let const_index = match const_index {
+        Some(index) => index,
+        None => return Ok((None, tokens)),
+    };
+
+    let visibility = tokens[..const_index]
+        .iter()
+        .map(|token| token.to_string())
+        .collect::<Vec<String>>()
+        .join("");
+    let name = match tokens.get(const_index + 1) {
+        Some(TokenTree::Ident(name)) => name.clone(),
+        _ => return Err((tokens[const_index].span(), "Expected a constant name after `const`".to_string())),
+    };
+    match tokens.get(const_index + 2) {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ';' => {},
+        _ => return Err((name.span(), "Expected `;` after the constant name".to_string())),
+    }
+
+    Ok((Some((visibility, name)), &tokens[const_index + 3..]))
+}
+
+// Writes the items back as one line of text, with the offset and span of every token
+fn assemble(tokens: &[TokenTree]) -> Result<Vec<u8>, MacroError> {
+    let mut text = String::new();
+    let mut offsets: Vec<(usize, Span)> = vec![];
+
+    for token in tokens {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        offsets.push((text.chars().count(), token.span()));
+
+        // The source text keeps names such as `Off-Hook` as they were written
+        let token_text = match token {
+            TokenTree::Group(group) => group.span().source_text().unwrap_or_else(|| group.to_string()),
+            _ => token.to_string(),
+        };
+        text.push_str(&token_text.replace(['\r', '\n'], " "));
+    }
+
+    assembler::assemble(&text).map_err(|error| {
+        let span = offsets
+            .iter()
+            .rev()
+            .find(|(offset, _)| *offset < error.column)
+            .map(|(_, span)| *span)
+            .unwrap_or_else(Span::call_site);
+        (span, error.kind.to_string())
+    })
+}
+
+fn compile_error(span: Span, message: &str) -> TokenStream {
+    let mut message = Literal::string(message);
+    message.set_span(span);
+    // Braces make it valid both as an item and as an expression
+    let mut arguments = Group::new(Delimiter::Brace, TokenStream::from(TokenTree::Literal(message)));
+    arguments.set_span(span);
+    let mut bang = Punct::new('!', Spacing::Alone);
+    bang.set_span(span);
+
+    [
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct(bang),
+        TokenTree::Group(arguments),
+    ]
+    .into_iter()
+    .collect()
+}
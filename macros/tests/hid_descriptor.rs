@@ -0,0 +1,66 @@
+use usb_hid_descriptor_macros::hid_descriptor;
+use usb_hid_descriptor_parser::hid::descriptor;
+
+hid_descriptor! {
+    const HEADSET;
+
+    Usage Page (Telephony Page)
+    Usage (Headset)
+    Collection (Application)
+        Report ID (2)
+        Logical Minimum (0)
+        Logical Maximum (1)
+        Report Size (1)
+        Usage (Hook Switch)
+        Usage (Phone Mute)
+        Report Count (2)
+        Input (Data, Variable, Absolute)
+        Report Count (6)
+        Input (Constant, Array, Absolute)
+        Push
+        Usage Page (LED Page)
+        Usage (Off-Hook)
+        Report Count (1)
+        Output (Data, Variable, Absolute, No Preferred State)
+        Pop
+        Usage (Consumer Page: Volume Increment)
+        Logical Minimum (-256)
+        Logical Maximum (0xFF)
+        Report Size (16)
+        Input (Data, Variable, Relative)
+    End Collection
+}
+
+#[test]
+fn const_descriptor() {
+    assert_eq!(
+        HEADSET,
+        [
+            0x05, 0x0B, 0x09, 0x05, 0xA1, 0x01, 0x85, 0x02, 0x15, 0x00, 0x25, 0x01, 0x75, 0x01, 0x09, 0x20, 0x09, 0x2F,
+            0x95, 0x02, 0x81, 0x02, 0x95, 0x06, 0x81, 0x01, 0xA4, 0x05, 0x08, 0x09, 0x17, 0x95, 0x01, 0x91, 0x22, 0xB4,
+            0x0B, 0xE9, 0x00, 0x0C, 0x00, 0x16, 0x00, 0xFF, 0x26, 0xFF, 0x00, 0x75, 0x10, 0x81, 0x06, 0xC0,
+        ]
+    );
+}
+
+#[test]
+fn printed_items_round_trip() {
+    // The output of HidItem's Display, item types included
+    let bytes = hid_descriptor! {
+        Usage Page (Generic Desktop Page) [Global]
+        Usage (Mouse) [Local]
+        Collection (Application) [Main]
+        Usage Minimum (Button Page: Button 1 (primary/trigger)) [Local]
+        Usage Maximum (Button Page: Button 3 (tertiary)) [Local]
+        Report Size (1) [Global]
+        Report Count (3) [Global]
+        Input (Data, Variable, Absolute, No Wrap, Linear, Preferred State, No Null State, Bit Field) [Main]
+        End Collection [Main]
+    };
+
+    let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+    let printed: Vec<String> = report.items.iter().map(|item| item.to_string()).collect();
+    assert_eq!(printed[0], "Usage Page (Generic Desktop Page) [Global]");
+    assert_eq!(printed[3], "Usage Minimum (Button Page: Button 1 (primary/trigger)) [Local]");
+    assert_eq!(report.to_bytes(), bytes.to_vec());
+}
@@ -1,22 +1,43 @@
+pub mod assembler;
+pub mod builder;
+pub mod c_export;
+pub mod class_descriptor;
+pub mod codegen;
+pub mod collection;
+pub mod decoder;
+pub mod encoder;
+pub mod formatter;
+pub mod input;
+pub mod layout;
+pub mod serializer;
+
 pub mod descriptor {
-    use std::{collections::HashMap, fmt};
+    use std::fmt;
 
-    #[derive(Debug)]
+    #[derive(Debug, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct Report {
         pub items: Vec<HidItem>,
     }
     
     impl Report {
         pub fn new() -> Self {
-            let report = Report {
+            Report {
                 items: vec![],
-            };
+            }
+        }
 
-            report
+        /// Serializes the report descriptor, see [`super::serializer::encode_item`].
+        pub fn to_bytes(&self) -> Vec<u8> {
+            self.items
+                .iter()
+                .flat_map(super::serializer::encode_item)
+                .collect()
         }
     }
 
     #[derive(Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize))]
     pub struct HidItem {
         pub item_type: HidItemType,
         pub size_bytes: u8,
@@ -24,45 +45,105 @@ pub mod descriptor {
         pub main_tag: Option<HidMainTag>,
         pub global_tag: Option<HidGlobalTag>,
         pub local_tag: Option<HidLocalTag>,
+        pub long_item: Option<HidLongItem>,
+        pub global_state: Option<GlobalState>,
+        pub usage: Option<Usage>,
 
         pub bytes: Vec<u8>,
     }
 
+    impl HidItem {
+        /// The data bytes of the item, without the prefix.
+        pub fn data(&self) -> &[u8] {
+            match &self.long_item {
+                Some(long_item) => &long_item.data,
+                None => &self.bytes[1..],
+            }
+        }
+
+        /// Whether the item is a Usage, Usage Minimum or Usage Maximum that selects its own usage page.
+        pub fn is_extended_usage(&self) -> bool {
+            self.usage.is_some() && self.data().len() == 4
+        }
+
+        /// The data of a short item read as an unsigned little-endian value.
+        pub fn unsigned_data(&self) -> u32 {
+            let mut value: u32 = 0;
+
+            for (i, data) in self.data().iter().take(4).enumerate() {
+                value |= (*data as u32) << (i * 8)
+            }
+
+            value
+        }
+
+        /// The item as it's printed, without its item type.
+        pub fn text(&self) -> String {
+            match &self.item_type {
+                HidItemType::Main => get_main_tag_name(self),
+                HidItemType::Global => get_global_tag_name(self),
+                HidItemType::Local => get_local_tag_name(self),
+                HidItemType::Long => get_long_item_name(self),
+                HidItemType::Unknown => "".to_string(),
+            }
+        }
+
+        /// The item type as it's printed, as in `Global`.
+        pub fn item_type_name(&self) -> &'static str {
+            match &self.item_type {
+                HidItemType::Main => "Main",
+                HidItemType::Global => "Global",
+                HidItemType::Local => "Local",
+                HidItemType::Long => "Long",
+                HidItemType::Unknown => "Unknown",
+            }
+        }
+    }
+
     impl fmt::Display for HidItem {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            let print_data: (String, String) = match &self.item_type {
-                HidItemType::Main => ("Main".to_string(), get_main_tag_name(self)),
-                HidItemType::Global => (
-                    "Global".to_string(),
-                    get_global_tag_name(self),
-                ),
-                HidItemType::Local => (
-                    "Local".to_string(),
-                    get_local_tag_name(self),
-                ),
-                HidItemType::Unknown => ("Unknown".to_string(), "".to_string()),
-            };
+            write!(f, "{} [{}]", self.text(), self.item_type_name())
+        }
+    }
 
-            write!(f, "{} [{}]", print_data.1, print_data.0)
+    /// Serialized with the fields of the item, and its printed `text` with the usage names resolved.
+    #[cfg(feature = "serde")]
+    impl serde::Serialize for HidItem {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use serde::ser::SerializeStruct;
+
+            let mut item = serializer.serialize_struct("HidItem", 11)?;
+            item.serialize_field("item_type", &self.item_type)?;
+            item.serialize_field("size_bytes", &self.size_bytes)?;
+            item.serialize_field("usage_page", &self.usage_page)?;
+            item.serialize_field("main_tag", &self.main_tag)?;
+            item.serialize_field("global_tag", &self.global_tag)?;
+            item.serialize_field("local_tag", &self.local_tag)?;
+            item.serialize_field("long_item", &self.long_item)?;
+            item.serialize_field("global_state", &self.global_state)?;
+            item.serialize_field("usage", &self.usage)?;
+            item.serialize_field("bytes", &self.bytes)?;
+            item.serialize_field("text", &self.text())?;
+            item.end()
         }
     }
 
     fn get_main_tag_name(item: &HidItem) -> String {
-        return match &item.main_tag {
+        match &item.main_tag {
             Some(tag) => match tag {
                 HidMainTag::Input(data) => format!("Input ({})", data),
                 HidMainTag::Output(data) => format!("Output ({})", data),
                 HidMainTag::Feature(data) => format!("Feature ({})", data),
                 HidMainTag::Collection(col_type) => format!("Collection ({})", col_type),
-                HidMainTag::EndCollection => format!("End Collection"),
+                HidMainTag::EndCollection => "End Collection".to_string(),
                 HidMainTag::Reserved => "Reserved".to_string(),
             },
             None => "".to_string(),
-        };
+        }
     }
 
     fn get_global_tag_name(item: &HidItem) -> String {
-        return match &item.global_tag {
+        match &item.global_tag {
             Some(tag) => match tag {
                 // TODO: Should UsagePage be u16?
                 HidGlobalTag::UsagePage(value) => format!("Usage Page ({})", super::name::get_usage_page_name(*value)),
@@ -80,15 +161,15 @@ pub mod descriptor {
                 HidGlobalTag::Reserved(value) => format!("Reserved ({})", value),
             },
             None => "".to_string(),
-        };
+        }
     }
 
     fn get_local_tag_name(item: &HidItem) -> String {
-        return match &item.local_tag {
+        match &item.local_tag {
             Some(tag) => match tag {
-                HidLocalTag::Usage(value) => format!("Usage ({})", super::name::get_usage_name(item.usage_page, *value)),
-                HidLocalTag::UsageMinimum(value) => format!("Usage Minimum ({})", value),
-                HidLocalTag::UsageMaximum(value) => format!("Usage Maximum ({})", value),
+                HidLocalTag::Usage(value) => format!("Usage ({})", get_usage_text(item, *value)),
+                HidLocalTag::UsageMinimum(value) => format!("Usage Minimum ({})", get_usage_text(item, *value)),
+                HidLocalTag::UsageMaximum(value) => format!("Usage Maximum ({})", get_usage_text(item, *value)),
                 HidLocalTag::DesignatorIndex(value) => format!("Designator Index ({})", value),
                 HidLocalTag::DesignatorMinimum(value) => format!("Designator Minimum ({})", value),
                 HidLocalTag::DesignatorMaximum(value) => format!("Designator Maximum ({})", value),
@@ -99,18 +180,161 @@ pub mod descriptor {
                 HidLocalTag::Reserved(value) => format!("Reserved ({})", value),
             },
             None => "".to_string(),
-        };
+        }
+    }
+
+    fn get_usage_text(item: &HidItem, value: u32) -> String {
+        match &item.usage {
+            Some(usage) if item.is_extended_usage() => format!(
+                "{}: {}",
+                super::name::get_usage_page_name(usage.page),
+                super::name::get_usage_name(Some(usage.page), usage.id)
+            ),
+            Some(usage) => super::name::get_usage_name(Some(usage.page), usage.id),
+            None => super::name::get_usage_name(item.usage_page, value),
+        }
+    }
+
+    fn get_long_item_name(item: &HidItem) -> String {
+        match &item.long_item {
+            Some(long_item) => format!("Long Item ({:#04X}, {} bytes)", long_item.tag, long_item.data.len()),
+            None => "".to_string(),
+        }
     }
 
     #[derive(Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum HidItemType {
         Main,
         Global,
         Local,
+        Long,
         Unknown,
     }
 
+    /// A usage resolved to the usage page it belongs to.
+    ///
+    /// A 4 byte Usage, Usage Minimum or Usage Maximum is an extended usage, its high 16 bits
+    /// select the usage page and override the current Usage Page.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+    pub struct Usage {
+        pub page: u32,
+        pub id: u32,
+    }
+
+    impl Usage {
+        pub fn new(page: u32, id: u32) -> Self {
+            Usage { page, id }
+        }
+    }
+
+    /// Serialized with the names of its usage page and usage, which are ignored when deserializing.
+    #[cfg(feature = "serde")]
+    impl serde::Serialize for Usage {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use serde::ser::SerializeStruct;
+
+            let mut usage = serializer.serialize_struct("Usage", 4)?;
+            usage.serialize_field("page", &self.page)?;
+            usage.serialize_field("id", &self.id)?;
+            usage.serialize_field("page_name", super::name::get_usage_page_name(self.page).trim())?;
+            usage.serialize_field("name", super::name::get_usage_name(Some(self.page), self.id).trim())?;
+            usage.end()
+        }
+    }
+
+    /// The global item state table, as in effect when a main item is encountered.
+    ///
+    /// Global items persist across collections until they are changed, or restored by a Pop item.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct GlobalState {
+        pub usage_page: Option<u32>,
+        pub logical_minimum: Option<i32>,
+        pub logical_maximum: Option<i32>,
+        pub physical_minimum: Option<i32>,
+        pub physical_maximum: Option<i32>,
+        pub unit_exponent: Option<i32>,
+        pub unit: Option<i32>,
+        pub report_size: Option<u32>,
+        pub report_id: Option<u32>,
+        pub report_count: Option<u32>,
+
+        // Logical and Physical Maximum read as unsigned values, see `logical_extents`
+        pub logical_maximum_unsigned: Option<u32>,
+        pub physical_maximum_unsigned: Option<u32>,
+    }
+
+    impl GlobalState {
+        fn update(&mut self, item: &HidItem) {
+            let tag = match &item.global_tag {
+                Some(tag) => tag,
+                None => return,
+            };
+            match tag {
+                HidGlobalTag::UsagePage(value) => self.usage_page = Some(*value),
+                HidGlobalTag::LogicalMinimum(value) => self.logical_minimum = Some(*value),
+                HidGlobalTag::LogicalMaximum(value) => {
+                    self.logical_maximum = Some(*value);
+                    self.logical_maximum_unsigned = Some(item.unsigned_data());
+                },
+                HidGlobalTag::PhysicalMinimum(value) => self.physical_minimum = Some(*value),
+                HidGlobalTag::PhysicalMaximum(value) => {
+                    self.physical_maximum = Some(*value);
+                    self.physical_maximum_unsigned = Some(item.unsigned_data());
+                },
+                HidGlobalTag::UnitExponent(value) => self.unit_exponent = Some(*value),
+                HidGlobalTag::Unit(value) => self.unit = Some(*value),
+                HidGlobalTag::ReportSize(value) => self.report_size = Some(*value),
+                HidGlobalTag::ReportId(value) => self.report_id = Some(*value),
+                HidGlobalTag::ReportCount(value) => self.report_count = Some(*value),
+                HidGlobalTag::Push(_) | HidGlobalTag::Pop(_) | HidGlobalTag::Reserved(_) => {},
+            }
+        }
+
+        /// Logical Minimum and Maximum, with the Maximum read as unsigned when the Minimum is not negative.
+        ///
+        /// This is how Linux and Windows treat e.g. `0x15 0x00 0x25 0xFF`, which strictly declares
+        /// the range 0 to -1, as the range 0 to 255.
+        pub fn logical_extents(&self) -> Option<(i64, i64)> {
+            get_extents(self.logical_minimum, self.logical_maximum, self.logical_maximum_unsigned)
+        }
+
+        /// Physical Minimum and Maximum, interpreted like [`GlobalState::logical_extents`].
+        ///
+        /// When both are 0 or undefined the logical extents are returned, as the spec requires.
+        pub fn physical_extents(&self) -> Option<(i64, i64)> {
+            let undefined = self.physical_minimum.unwrap_or(0) == 0 && self.physical_maximum.unwrap_or(0) == 0;
+            if undefined {
+                return self.logical_extents();
+            }
+
+            get_extents(self.physical_minimum, self.physical_maximum, self.physical_maximum_unsigned)
+        }
+    }
+
+    fn get_extents(minimum: Option<i32>, maximum: Option<i32>, maximum_unsigned: Option<u32>) -> Option<(i64, i64)> {
+        let minimum = minimum?;
+        let maximum = if minimum < 0 {
+            maximum? as i64
+        } else {
+            maximum_unsigned? as i64
+        };
+
+        Some((minimum as i64, maximum))
+    }
+
+    /// A long item, its tag and data are not interpreted by the HID specification.
+    #[derive(Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct HidLongItem {
+        pub tag: u8,
+        pub data: Vec<u8>,
+    }
+
     #[derive(Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum HidMainTag {
         Input(MainInputData),
         Output(MainOutputData),
@@ -121,6 +345,7 @@ pub mod descriptor {
     }
 
     #[derive(Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum HidGlobalTag {
         UsagePage(u32),
         LogicalMinimum(i32),
@@ -137,7 +362,94 @@ pub mod descriptor {
         Reserved(i32),
     }
 
+    /// The usage pages defined by the HID Usage Tables, for use where a usage page number is expected.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum UsagePage {
+        GenericDesktop,
+        SimulationControls,
+        VrControls,
+        SportControls,
+        GameControls,
+        GenericDeviceControls,
+        KeyboardKeypad,
+        Led,
+        Button,
+        Ordinal,
+        Telephony,
+        Consumer,
+        Digitizers,
+        Haptics,
+        PhysicalInterfaceDevice,
+        Unicode,
+        EyeAndHeadTrackers,
+        AuxiliaryDisplay,
+        Sensors,
+        MedicalInstrument,
+        BrailleDisplay,
+        LightingAndIllumination,
+        Monitor,
+        MonitorEnumerated,
+        VesaVirtualControls,
+        Power,
+        BatterySystem,
+        BarCodeScanner,
+        Scale,
+        MagneticStripeReading,
+        PointOfSale,
+        CameraControl,
+        Arcade,
+        GamingDevice,
+        FidoAlliance,
+        // 0xFF00 - 0xFFFF, holds the low byte
+        VendorDefined(u8),
+    }
+
+    impl From<UsagePage> for u32 {
+        fn from(usage_page: UsagePage) -> Self {
+            match usage_page {
+                UsagePage::GenericDesktop => 0x01,
+                UsagePage::SimulationControls => 0x02,
+                UsagePage::VrControls => 0x03,
+                UsagePage::SportControls => 0x04,
+                UsagePage::GameControls => 0x05,
+                UsagePage::GenericDeviceControls => 0x06,
+                UsagePage::KeyboardKeypad => 0x07,
+                UsagePage::Led => 0x08,
+                UsagePage::Button => 0x09,
+                UsagePage::Ordinal => 0x0A,
+                UsagePage::Telephony => 0x0B,
+                UsagePage::Consumer => 0x0C,
+                UsagePage::Digitizers => 0x0D,
+                UsagePage::Haptics => 0x0E,
+                UsagePage::PhysicalInterfaceDevice => 0x0F,
+                UsagePage::Unicode => 0x10,
+                UsagePage::EyeAndHeadTrackers => 0x12,
+                UsagePage::AuxiliaryDisplay => 0x14,
+                UsagePage::Sensors => 0x20,
+                UsagePage::MedicalInstrument => 0x40,
+                UsagePage::BrailleDisplay => 0x41,
+                UsagePage::LightingAndIllumination => 0x59,
+                UsagePage::Monitor => 0x80,
+                UsagePage::MonitorEnumerated => 0x81,
+                UsagePage::VesaVirtualControls => 0x82,
+                UsagePage::Power => 0x84,
+                UsagePage::BatterySystem => 0x85,
+                UsagePage::BarCodeScanner => 0x8C,
+                UsagePage::Scale => 0x8D,
+                UsagePage::MagneticStripeReading => 0x8E,
+                UsagePage::PointOfSale => 0x8F,
+                UsagePage::CameraControl => 0x90,
+                UsagePage::Arcade => 0x91,
+                UsagePage::GamingDevice => 0x92,
+                UsagePage::FidoAlliance => 0xF1D0,
+                UsagePage::VendorDefined(value) => 0xFF00 | value as u32,
+            }
+        }
+    }
+
     #[derive(Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum HidLocalTag {
         Usage(u32),
         UsageMinimum(u32),
@@ -152,7 +464,8 @@ pub mod descriptor {
         Reserved(u32),
     }
 
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct MainInputData {
         pub item_type: ItemType,
         pub data_type: DataType,
@@ -164,6 +477,68 @@ pub mod descriptor {
         pub field_type: FieldType,
     }
     
+    impl MainInputData {
+        pub fn from_bits(bits: u32) -> Self {
+            // Bit 0 {Data (0) | Constant (1)}
+            // Bit 1 {Array (0) | Variable (1)}
+            // Bit 2 {Absolute (0) | Relative (1)}
+            // Bit 3 {No Wrap (0) | Wrap (1)}
+            // Bit 4 {Linear (0) | Non Linear (1)}
+            // Bit 5 {Preferred State (0) | No Preferred (1)}
+            // Bit 6 {No Null position (0) | Null state(1)}
+            // Bit 7 Reserved (0)
+            // Bit 8 {Bit Field (0) | Buffered Bytes (1)}
+            // Bit 31-9 Reserved (0)
+            MainInputData {
+                item_type: match bits & 0b00000001 {
+                    0 => ItemType::Data,
+                    _ => ItemType::Constant,
+                },
+                data_type: match (bits & 0b00000010) >> 1 {
+                    0 => DataType::Array,
+                    _ => DataType::Variable,
+                },
+                data_point: match (bits & 0b00000100) >> 2 {
+                    0 => DataPoint::Absolute,
+                    _ => DataPoint::Relative,
+                },
+                wrapping: match (bits & 0b00001000) >> 3 {
+                    0 => Wrapping::NoWrap,
+                    _ => Wrapping::Wrap,
+                },
+                linearity: match (bits & 0b00010000) >> 4 {
+                    0 => Linearity::Linear,
+                    _ => Linearity::Nonlinear,
+                },
+                state_preferrence: match (bits & 0b00100000) >> 5 {
+                    0 => StatePreference::PreferredState,
+                    _ => StatePreference::NoPreferredState,
+                },
+                null_state: match (bits & 0b01000000) >> 6 {
+                    0 => NullState::NoNullState,
+                    _ => NullState::NullState,
+                },
+                field_type: match (bits & 0b1_00000000) >> 8 {
+                    0 => FieldType::BitField,
+                    _ => FieldType::BufferedBytes,
+                },
+            }
+        }
+
+        /// The flag bits, with the reserved bits cleared. [`super::serializer::encode_item`] keeps those of the item.
+        pub fn to_bits(&self) -> u32 {
+            // The first variant of every flag is the cleared bit
+            (self.item_type as u32)
+                | (self.data_type as u32) << 1
+                | (self.data_point as u32) << 2
+                | (self.wrapping as u32) << 3
+                | (self.linearity as u32) << 4
+                | (self.state_preferrence as u32) << 5
+                | (self.null_state as u32) << 6
+                | (self.field_type as u32) << 8
+        }
+    }
+
     impl fmt::Display for MainInputData {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             let item_type = match self.item_type {
@@ -203,7 +578,8 @@ pub mod descriptor {
         }
     }
 
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct MainOutputData {
         pub item_type: ItemType,
         pub data_type: DataType,
@@ -216,6 +592,73 @@ pub mod descriptor {
         pub field_type: FieldType,
     }
 
+    impl MainOutputData {
+        pub fn from_bits(bits: u32) -> Self {
+            // Bit 0 {Data (0) | Constant (1)}
+            // Bit 1 {Array (0) | Variable (1)}
+            // Bit 2 {Absolute (0) | Relative (1)}
+            // Bit 3 {No Wrap (0) | Wrap (1)}
+            // Bit 4 {Linear (0) | Non Linear (1)}
+            // Bit 5 {Preferred State (0) | No Preferred (1)}
+            // Bit 6 {No Null position (0) | Null state(1)}
+            // Bit 7 {Non Volatile (0) | Volatile (1)}
+            // Bit 8 {Bit Field (0) | Buffered Bytes (1)}
+            // Bit 31-9 Reserved (0)
+            MainOutputData {
+                item_type: match bits & 0b00000001 {
+                    0 => ItemType::Data,
+                    _ => ItemType::Constant,
+                },
+                data_type: match (bits & 0b00000010) >> 1 {
+                    0 => DataType::Array,
+                    _ => DataType::Variable,
+                },
+                data_point: match (bits & 0b00000100) >> 2 {
+                    0 => DataPoint::Absolute,
+                    _ => DataPoint::Relative,
+                },
+                wrapping: match (bits & 0b00001000) >> 3 {
+                    0 => Wrapping::NoWrap,
+                    _ => Wrapping::Wrap,
+                },
+                linearity: match (bits & 0b00010000) >> 4 {
+                    0 => Linearity::Linear,
+                    _ => Linearity::Nonlinear,
+                },
+                state_preferrence: match (bits & 0b00100000) >> 5 {
+                    0 => StatePreference::PreferredState,
+                    _ => StatePreference::NoPreferredState,
+                },
+                null_state: match (bits & 0b01000000) >> 6 {
+                    0 => NullState::NoNullState,
+                    _ => NullState::NullState,
+                },
+                volatility: match (bits & 0b10000000) >> 7 {
+                    0 => Volatility::NonVolatile,
+                    _ => Volatility::Volatile,
+                },
+                field_type: match (bits & 0b1_00000000) >> 8 {
+                    0 => FieldType::BitField,
+                    _ => FieldType::BufferedBytes,
+                },
+            }
+        }
+
+        /// The flag bits, with the reserved bits cleared. [`super::serializer::encode_item`] keeps those of the item.
+        pub fn to_bits(&self) -> u32 {
+            // The first variant of every flag is the cleared bit
+            (self.item_type as u32)
+                | (self.data_type as u32) << 1
+                | (self.data_point as u32) << 2
+                | (self.wrapping as u32) << 3
+                | (self.linearity as u32) << 4
+                | (self.state_preferrence as u32) << 5
+                | (self.null_state as u32) << 6
+                | (self.volatility as u32) << 7
+                | (self.field_type as u32) << 8
+        }
+    }
+
     impl fmt::Display for MainOutputData {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             let item_type = match self.item_type {
@@ -259,61 +702,80 @@ pub mod descriptor {
         }
     }
 
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum ItemType {
+        #[default]
         Data,
         Constant,
     }
 
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum DataType {
+        #[default]
         Array,
         Variable,
     }
 
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum DataPoint {
+        #[default]
         Absolute,
         Relative,
     }
 
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum Wrapping {
+        #[default]
         NoWrap,
         Wrap,
     }
 
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum Linearity {
+        #[default]
         Linear,
         Nonlinear,
     }
 
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum StatePreference {
+        #[default]
         PreferredState,
         NoPreferredState,
     }
 
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum NullState {
+        #[default]
         NoNullState,
         NullState,
     }
 
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum Volatility {
+        #[default]
         NonVolatile,
         Volatile,
     }
 
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum FieldType {
+        #[default]
         BitField,
         BufferedBytes,
     }
 
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum CollectionType {
         Physical,
         Application,
@@ -323,6 +785,24 @@ pub mod descriptor {
         UsageSwitch,
         UsageModifier,
         Reserved,
+        VendorDefined(u8),
+    }
+
+    impl CollectionType {
+        /// The data value of the Collection item, there is none for Reserved collections.
+        pub fn to_value(&self) -> Option<u8> {
+            match self {
+                CollectionType::Physical => Some(0x00),
+                CollectionType::Application => Some(0x01),
+                CollectionType::Logical => Some(0x02),
+                CollectionType::Report => Some(0x03),
+                CollectionType::NamedArray => Some(0x04),
+                CollectionType::UsageSwitch => Some(0x05),
+                CollectionType::UsageModifier => Some(0x06),
+                CollectionType::Reserved => None,
+                CollectionType::VendorDefined(value) => Some(*value),
+            }
+        }
     }
 
     impl fmt::Display for CollectionType {
@@ -336,12 +816,92 @@ pub mod descriptor {
                 CollectionType::UsageSwitch => "Usage Switch",
                 CollectionType::UsageModifier => "Usage Modifier",
                 CollectionType::Reserved => "Reserved",
+                CollectionType::VendorDefined(value) => return write!(f, "Vendor Defined {:#04X}", value),
             };
 
             write!(f, "{}", col_type)
         }
     }
 
+    /// Error returned by [`try_get_descriptor_report`] for a malformed report descriptor.
+    ///
+    /// Every variant carries the byte offset of the offending item and its prefix byte.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum ParseError {
+        /// The item announces more data bytes than are left in the descriptor.
+        UnexpectedEnd {
+            offset: usize,
+            prefix: u8,
+            expected: usize,
+            available: usize,
+        },
+        /// Main item tags 0b0000 - 0b0111 are not defined by the HID specification.
+        InvalidMainTag { offset: usize, prefix: u8 },
+        /// Local item tag 0b0110 is not defined by the HID specification.
+        InvalidLocalTag { offset: usize, prefix: u8 },
+        /// A Pop item without a matching Push item.
+        PopWithoutPush { offset: usize, prefix: u8 },
+        /// An End Collection item without a matching Collection item.
+        UnbalancedEndCollection { offset: usize, prefix: u8 },
+        /// A Collection item that is never closed by an End Collection item.
+        UnclosedCollection { offset: usize, prefix: u8 },
+    }
+
+    impl ParseError {
+        pub fn offset(&self) -> usize {
+            match self {
+                ParseError::UnexpectedEnd { offset, .. } => *offset,
+                ParseError::InvalidMainTag { offset, .. } => *offset,
+                ParseError::InvalidLocalTag { offset, .. } => *offset,
+                ParseError::PopWithoutPush { offset, .. } => *offset,
+                ParseError::UnbalancedEndCollection { offset, .. } => *offset,
+                ParseError::UnclosedCollection { offset, .. } => *offset,
+            }
+        }
+
+        pub fn prefix(&self) -> u8 {
+            match self {
+                ParseError::UnexpectedEnd { prefix, .. } => *prefix,
+                ParseError::InvalidMainTag { prefix, .. } => *prefix,
+                ParseError::InvalidLocalTag { prefix, .. } => *prefix,
+                ParseError::PopWithoutPush { prefix, .. } => *prefix,
+                ParseError::UnbalancedEndCollection { prefix, .. } => *prefix,
+                ParseError::UnclosedCollection { prefix, .. } => *prefix,
+            }
+        }
+    }
+
+    impl fmt::Display for ParseError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                ParseError::UnexpectedEnd { offset, prefix, expected, available } => write!(
+                    f,
+                    "Item {:#04X} at offset {} expects {} data bytes, but only {} are left",
+                    prefix, offset, expected, available
+                ),
+                ParseError::InvalidMainTag { offset, prefix } => {
+                    write!(f, "Item {:#04X} at offset {} has an undefined main tag", prefix, offset)
+                },
+                ParseError::InvalidLocalTag { offset, prefix } => {
+                    write!(f, "Item {:#04X} at offset {} has an undefined local tag", prefix, offset)
+                },
+                ParseError::PopWithoutPush { offset, prefix } => {
+                    write!(f, "Item {:#04X} at offset {} pops an empty global item stack", prefix, offset)
+                },
+                ParseError::UnbalancedEndCollection { offset, prefix } => {
+                    write!(f, "Item {:#04X} at offset {} ends a collection that was never opened", prefix, offset)
+                },
+                ParseError::UnclosedCollection { offset, prefix } => {
+                    write!(f, "Item {:#04X} at offset {} opens a collection that is never closed", prefix, offset)
+                },
+            }
+        }
+    }
+
+    impl std::error::Error for ParseError {}
+
+    const LONG_ITEM_PREFIX: u8 = 0xFE;
+
     #[derive(Debug)]
     struct HidItemBuilder {
         pub usage_page: Option<u32>,
@@ -351,16 +911,14 @@ pub mod descriptor {
 
     impl HidItemBuilder {
         fn new(usage_page: Option<u32>, prefix_bits: u8) -> Self {
-            let builder = HidItemBuilder {
-                usage_page: usage_page,
-                prefix_bits: prefix_bits,
+            HidItemBuilder {
+                usage_page,
+                prefix_bits,
                 data_bits: vec![],
-            };
-
-            builder
+            }
         }
 
-        fn build(&self) -> HidItem {
+        fn build(&self, offset: usize) -> Result<HidItem, ParseError> {
             let item_type = self.get_item_type(self.prefix_bits);
             let size_bytes = self.get_size_bytes(self.prefix_bits);
 
@@ -369,23 +927,53 @@ pub mod descriptor {
                 Option<HidGlobalTag>,
                 Option<HidLocalTag>,
             ) = match &item_type {
-                HidItemType::Main => (Some(self.get_main_tag(self)), None, None),
+                HidItemType::Main => (Some(self.get_main_tag(self, offset)?), None, None),
                 HidItemType::Global => (None, Some(self.get_global_tag(self)), None),
-                HidItemType::Local => (None, None, Some(self.get_local_tag(self))),
+                HidItemType::Local => (None, None, Some(self.get_local_tag(self, offset)?)),
                 _ => (None, None, None),
             };
 
+            let usage = self.get_usage(tags.2.as_ref());
+
             let mut bytes: Vec<u8> = vec![];
             bytes.push(self.prefix_bits);
             bytes.extend(self.data_bits.iter());
-            HidItem {
-                item_type: item_type,
-                size_bytes: size_bytes,
+            Ok(HidItem {
+                item_type,
+                size_bytes,
                 usage_page: self.usage_page,
                 main_tag: tags.0,
                 global_tag: tags.1,
                 local_tag: tags.2,
-                bytes: bytes,
+                long_item: None,
+                global_state: None,
+                usage,
+                bytes,
+            })
+        }
+
+        fn build_long(&self) -> HidItem {
+            // Long items are laid out as 0xFE, bDataSize, bLongItemTag, data
+            let size = self.data_bits[0];
+            let tag = self.data_bits[1];
+
+            let mut bytes: Vec<u8> = vec![];
+            bytes.push(self.prefix_bits);
+            bytes.extend(self.data_bits.iter());
+            HidItem {
+                item_type: HidItemType::Long,
+                size_bytes: size,
+                usage_page: self.usage_page,
+                main_tag: None,
+                global_tag: None,
+                local_tag: None,
+                long_item: Some(HidLongItem {
+                    tag,
+                    data: self.data_bits[2..].to_vec(),
+                }),
+                global_state: None,
+                usage: None,
+                bytes,
             }
         }
 
@@ -397,152 +985,62 @@ pub mod descriptor {
             self.get_size_bytes(self.prefix_bits)
         }
 
+        fn is_long_item(&self) -> bool {
+            self.prefix_bits == LONG_ITEM_PREFIX
+        }
+
         fn get_item_type(&self, prefix_bits: u8) -> HidItemType {
             // Extract bits 2 - 3, e.g. 0b01010101
             //                                ^^
             let item_type_id = (prefix_bits & 0b00001100) >> 2;
-            return match item_type_id {
+            match item_type_id {
                 0 => HidItemType::Main,
                 1 => HidItemType::Global,
                 2 => HidItemType::Local,
                 _ => HidItemType::Unknown,
-            };
+            }
         }
     
         fn get_size_bytes(&self, prefix_bits: u8) -> u8 {
             // Extract bits 0 - 1, e.g. 0b01010101
             //                                  ^^
             let size_id = prefix_bits & 0b00000011;
-            return match size_id {
+            match size_id {
                 0 => 0,
                 1 => 1,
                 2 => 2,
-                3 => 4,
-                _ => todo!("How to handle illegal values?"),
-            };
+                _ => 4,
+            }
         }
 
-        fn get_main_tag(&self, item_builder: &HidItemBuilder) -> HidMainTag {
+        fn get_main_tag(&self, item_builder: &HidItemBuilder, offset: usize) -> Result<HidMainTag, ParseError> {
             // Extract bits 4 - 7, e.g. 0b01010101
             //                            ^^^^
             let tag = (item_builder.prefix_bits & 0b11110000) >> 4;
-            return match tag {
+            Ok(match tag {
                 0b1000 => HidMainTag::Input(self.get_main_input_data(&item_builder.data_bits)),
                 0b1001 => HidMainTag::Output(self.get_main_output_data(&item_builder.data_bits)),
                 0b1011 => HidMainTag::Feature(self.get_main_output_data(&item_builder.data_bits)),
                 0b1010 => HidMainTag::Collection(self.get_main_collection_type(&item_builder.data_bits)),
                 0b1100 => HidMainTag::EndCollection,
                 0b1101..=0b1111 => HidMainTag::Reserved,
-                _ => todo!("How to handle illegal values?"),
-            };
+                _ => return Err(ParseError::InvalidMainTag { offset, prefix: item_builder.prefix_bits }),
+            })
         }
     
-        fn get_main_input_data(&self, data_bits: &Vec<u8>) -> MainInputData {
-            let first_byte = data_bits[0];
-            let second_byte = if data_bits.len() > 1 { data_bits[1] } else { 0b00000000 };
-
-            // Bit 0 {Data (0) | Constant (1)}
-            // Bit 1 {Array (0) | Variable (1)}
-            // Bit 2 {Absolute (0) | Relative (1)}
-            // Bit 3 {No Wrap (0) | Wrap (1)}
-            // Bit 4 {Linear (0) | Non Linear (1)}
-            // Bit 5 {Preferred State (0) | No Preferred (1)}
-            // Bit 6 {No Null position (0) | Null state(1)}
-            // Bit 7 Reserved (0)
-            // Bit 8 {Bit Field (0) | Buffered Bytes (1)}
-            // Bit 31-9 Reserved (0)
-            MainInputData {
-                item_type: match first_byte & 0b00000001 {
-                    0 => ItemType::Data,
-                    _ => ItemType::Constant,
-                },
-                data_type: match (first_byte & 0b00000010) >> 1 {
-                    0 => DataType::Array,
-                    _ => DataType::Variable,
-                },
-                data_point: match (first_byte & 0b00000100) >> 2 {
-                    0 => DataPoint::Absolute,
-                    _ => DataPoint::Relative,
-                },
-                wrapping: match (first_byte & 0b00001000) >> 3 {
-                    0 => Wrapping::NoWrap,
-                    _ => Wrapping::Wrap,
-                },
-                linearity: match (first_byte & 0b00010000) >> 4 {
-                    0 => Linearity::Linear,
-                    _ => Linearity::Nonlinear,
-                },
-                state_preferrence: match (first_byte & 0b00100000) >> 5 {
-                    0 => StatePreference::PreferredState,
-                    _ => StatePreference::NoPreferredState,
-                },
-                null_state: match (first_byte & 0b01000000) >> 6 {
-                    0 => NullState::NoNullState,
-                    _ => NullState::NullState,
-                },
-                field_type: match second_byte & 0b00000001 {
-                    0 => FieldType::BitField,
-                    _ => FieldType::BufferedBytes,
-                },
-            }
+        fn get_main_input_data(&self, data_bits: &[u8]) -> MainInputData {
+            // A zero sized item has all of its data bits cleared
+            MainInputData::from_bits(self.get_u32(data_bits))
         }
     
-        fn get_main_output_data(&self, data_bits: &Vec<u8>) -> MainOutputData {
-            let first_byte = data_bits[0];
-            let second_byte = if data_bits.len() > 1 { data_bits[1] } else { 0b00000000 };
-
-            // Bit 0 {Data (0) | Constant (1)}
-            // Bit 1 {Array (0) | Variable (1)}
-            // Bit 2 {Absolute (0) | Relative (1)}
-            // Bit 3 {No Wrap (0) | Wrap (1)}
-            // Bit 4 {Linear (0) | Non Linear (1)}
-            // Bit 5 {Preferred State (0) | No Preferred (1)}
-            // Bit 6 {No Null position (0) | Null state(1)}
-            // Bit 7 {Non Volatile (0) | Volatile (1)}
-            // Bit 8 {Bit Field (0) | Buffered Bytes (1)}
-            // Bit 31- 9Reserved (0)
-            MainOutputData {
-                item_type: match first_byte & 0b00000001 {
-                    0 => ItemType::Data,
-                    _ => ItemType::Constant,
-                },
-                data_type: match (first_byte & 0b00000010) >> 1 {
-                    0 => DataType::Array,
-                    _ => DataType::Variable,
-                },
-                data_point: match (first_byte & 0b00000100) >> 2 {
-                    0 => DataPoint::Absolute,
-                    _ => DataPoint::Relative,
-                },
-                wrapping: match (first_byte & 0b00001000) >> 3 {
-                    0 => Wrapping::NoWrap,
-                    _ => Wrapping::Wrap,
-                },
-                linearity: match (first_byte & 0b00010000) >> 4 {
-                    0 => Linearity::Linear,
-                    _ => Linearity::Nonlinear,
-                },
-                state_preferrence: match (first_byte & 0b00100000) >> 5 {
-                    0 => StatePreference::PreferredState,
-                    _ => StatePreference::NoPreferredState,
-                },
-                null_state: match (first_byte & 0b01000000) >> 6 {
-                    0 => NullState::NoNullState,
-                    _ => NullState::NullState,
-                },
-                volatility: match (first_byte & 0b10000000) >> 7 {
-                    0 => Volatility::NonVolatile,
-                    _ => Volatility::Volatile,
-                },
-                field_type: match second_byte & 0b00000001 {
-                    0 => FieldType::BitField,
-                    _ => FieldType::BufferedBytes,
-                },
-            }
+        fn get_main_output_data(&self, data_bits: &[u8]) -> MainOutputData {
+            // A zero sized item has all of its data bits cleared
+            MainOutputData::from_bits(self.get_u32(data_bits))
         }
     
-        fn get_main_collection_type(&self, data_bits: &Vec<u8>) -> CollectionType {
-            let first_byte = data_bits[0];
+        fn get_main_collection_type(&self, data_bits: &[u8]) -> CollectionType {
+            // A zero sized Collection item is a Physical collection
+            let first_byte = data_bits.first().copied().unwrap_or(0x00);
             match first_byte {
                 0x00 => CollectionType::Physical,
                 0x01 => CollectionType::Application,
@@ -551,6 +1049,7 @@ pub mod descriptor {
                 0x04 => CollectionType::NamedArray,
                 0x05 => CollectionType::UsageSwitch,
                 0x06 => CollectionType::UsageModifier,
+                0x80..=0xFF => CollectionType::VendorDefined(first_byte),
                 _ => CollectionType::Reserved,
             }
         }
@@ -559,51 +1058,77 @@ pub mod descriptor {
             // Extract bits 4 - 7, e.g. 0b01010101
             //                            ^^^^
             let tag = (item_builder.prefix_bits & 0b11110000) >> 4;
-            return match tag {
+            match tag {
                 0b0000 => HidGlobalTag::UsagePage(self.get_u32(&item_builder.data_bits)),
                 0b0001 => HidGlobalTag::LogicalMinimum(self.get_i32(&item_builder.data_bits)),
                 0b0010 => HidGlobalTag::LogicalMaximum(self.get_i32(&item_builder.data_bits)),
                 0b0011 => HidGlobalTag::PhysicalMinimum(self.get_i32(&item_builder.data_bits)),
                 0b0100 => HidGlobalTag::PhysicalMaximum(self.get_i32(&item_builder.data_bits)),
-                0b0101 => HidGlobalTag::UnitExponent(self.get_i32(&item_builder.data_bits)),
-                0b0110 => HidGlobalTag::Unit(self.get_i32(&item_builder.data_bits)),
+                0b0101 => HidGlobalTag::UnitExponent(self.get_unit_exponent(&item_builder.data_bits)),
+                0b0110 => HidGlobalTag::Unit(self.get_u32(&item_builder.data_bits) as i32),
                 0b0111 => HidGlobalTag::ReportSize(self.get_u32(&item_builder.data_bits)),
                 0b1000 => HidGlobalTag::ReportId(self.get_u32(&item_builder.data_bits)),
                 0b1001 => HidGlobalTag::ReportCount(self.get_u32(&item_builder.data_bits)),
                 0b1010 => HidGlobalTag::Push(self.get_i32(&item_builder.data_bits)),
                 0b1011 => HidGlobalTag::Pop(self.get_i32(&item_builder.data_bits)),
-                0b1100..=0b1111 => HidGlobalTag::Reserved(self.get_i32(&item_builder.data_bits)),
-                _ => todo!("How to handle illegal values?"),
-            };
+                _ => HidGlobalTag::Reserved(self.get_i32(&item_builder.data_bits)),
+            }
         }
     
-        fn get_u32(&self, data_bits: &Vec<u8>) -> u32 {
+        fn get_u32(&self, data_bits: &[u8]) -> u32 {
             let mut value : u32 = 0;
     
             for (i, data) in data_bits.iter().enumerate() {
                 let d = *data;
-                value = value | ((d as u32) << (i * 8))
+                value |= (d as u32) << (i * 8)
             }
     
             value
         }
     
-        fn get_i32(&self, data_bits: &Vec<u8>) -> i32 {
-            let mut value : i32 = 0;
-    
-            for (i, data) in data_bits.iter().enumerate() {
-                let d = *data;
-                value = value | ((d as i32) << (i * 8))
+        fn get_i32(&self, data_bits: &[u8]) -> i32 {
+            // Signed data is two's complement in the size of the item, e.g. 0x81 is -127
+            match data_bits.len() {
+                0 => 0,
+                1 => data_bits[0] as i8 as i32,
+                2 => i16::from_le_bytes([data_bits[0], data_bits[1]]) as i32,
+                _ => self.get_u32(data_bits) as i32,
+            }
+        }
+
+        fn get_unit_exponent(&self, data_bits: &[u8]) -> i32 {
+            // The spec encodes the exponent as a 4 bit two's complement nibble, e.g. 0x0E is -2,
+            // but plenty of devices use a regular signed value instead. Accept both, like Linux does.
+            let value = self.get_u32(data_bits);
+            if value & 0xFFFFFFF0 == 0 {
+                ((value << 28) as i32) >> 28
+            } else {
+                self.get_i32(data_bits)
             }
-    
-            value
         }
     
-        fn get_local_tag(&self, item_builder: &HidItemBuilder) -> HidLocalTag {
+        fn get_usage(&self, local_tag: Option<&HidLocalTag>) -> Option<Usage> {
+            let value = match local_tag {
+                Some(HidLocalTag::Usage(value))
+                | Some(HidLocalTag::UsageMinimum(value))
+                | Some(HidLocalTag::UsageMaximum(value)) => *value,
+                _ => return None,
+            };
+
+            if self.data_bits.len() == 4 {
+                // Extended usage, the usage page is in the high 16 bits
+                Some(Usage::new(value >> 16, value & 0xFFFF))
+            } else {
+                // Without a Usage Page the usage is on the Undefined page
+                Some(Usage::new(self.usage_page.unwrap_or(0), value))
+            }
+        }
+
+        fn get_local_tag(&self, item_builder: &HidItemBuilder, offset: usize) -> Result<HidLocalTag, ParseError> {
             // Extract bits 4 - 7, e.g. 0b01010101
             //                            ^^^^
             let tag = (item_builder.prefix_bits & 0b11110000) >> 4;
-            return match tag {
+            Ok(match tag {
                 0b0000 => HidLocalTag::Usage(self.get_u32(&item_builder.data_bits)),
                 0b0001 => HidLocalTag::UsageMinimum(self.get_u32(&item_builder.data_bits)),
                 0b0010 => HidLocalTag::UsageMaximum(self.get_u32(&item_builder.data_bits)),
@@ -616,70 +1141,78 @@ pub mod descriptor {
                 0b1001 => HidLocalTag::StringMaximum(self.get_u32(&item_builder.data_bits)),
                 0b1010 => HidLocalTag::Delimiter(self.get_u32(&item_builder.data_bits)),
                 0b1011..=0b1111 => HidLocalTag::Reserved(self.get_u32(&item_builder.data_bits)),
-                _ => todo!("How to handle illegal values?"),
-            };
+                _ => return Err(ParseError::InvalidLocalTag { offset, prefix: item_builder.prefix_bits }),
+            })
         }
     }
 
+    /// Parses a report descriptor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the descriptor is malformed, see [`try_get_descriptor_report`].
     pub fn get_descriptor_report(bytes: &[u8]) -> Report {
+        match try_get_descriptor_report(bytes) {
+            Ok(report) => report,
+            Err(error) => panic!("Invalid report descriptor: {}", error),
+        }
+    }
+
+    /// Parses a report descriptor, returning an error instead of panicking on malformed input.
+    pub fn try_get_descriptor_report(bytes: &[u8]) -> Result<Report, ParseError> {
         let mut report = Report::new();
 
         let mut i = 0;
-        let mut collection_index = 0;
-        let mut usage_pages: HashMap<i32, u32> = HashMap::new();
+        let mut global_state = GlobalState::default();
+        let mut global_stack: Vec<GlobalState> = vec![];
         while i < bytes.len() {
+            let offset = i;
             let prefix_bits = bytes[i];
 
-            let usage_page = match usage_pages.get(&collection_index) {
-                Some(val) => Some(val.clone()),
-                None => None,
+            let mut item_builder = HidItemBuilder::new(global_state.usage_page, prefix_bits);
+            let available = bytes.len() - offset - 1;
+            let size = if item_builder.is_long_item() {
+                if available < 2 {
+                    return Err(ParseError::UnexpectedEnd { offset, prefix: prefix_bits, expected: 2, available });
+                }
+                // bDataSize and bLongItemTag, followed by the data
+                2 + bytes[i + 1] as usize
+            } else {
+                item_builder.get_size() as usize
             };
-            let mut item_builder = HidItemBuilder::new(usage_page, prefix_bits);
-            for _ in 0..item_builder.get_size() {
+            if size > available {
+                return Err(ParseError::UnexpectedEnd { offset, prefix: prefix_bits, expected: size, available });
+            }
+            for _ in 0..size {
                 i += 1;
                 let item_data = bytes[i];
                 item_builder.push_data(item_data);
             }
-            report.items.push(item_builder.build());
             i += 1;
 
-            let item = item_builder.build();
+            let mut item = if item_builder.is_long_item() {
+                item_builder.build_long()
+            } else {
+                item_builder.build(offset)?
+            };
             match &item.global_tag {
-                Some(tag) => {
-                    match tag {
-                        HidGlobalTag::UsagePage(up) => {
-                            usage_pages.insert(collection_index, up.clone());
-                        },
-                        _ => {},
-                    }
-                }
-                _ => {},
-            }
-            match &item.main_tag {
-                Some(tag) => {
-                    match tag {
-                        HidMainTag::EndCollection => {
-                            usage_pages.remove(&collection_index);
-                            collection_index -= 1;
-                        },
-                        _ => {},
-                    }
+                Some(HidGlobalTag::Push(_)) => global_stack.push(global_state.clone()),
+                Some(HidGlobalTag::Pop(_)) => {
+                    global_state = match global_stack.pop() {
+                        Some(state) => state,
+                        None => return Err(ParseError::PopWithoutPush { offset, prefix: prefix_bits }),
+                    };
                 },
+                Some(_) => global_state.update(&item),
                 None => {},
             }
-
-            match &item.main_tag {
-                Some(tag) => {
-                    match tag {
-                        HidMainTag::Collection(_) => collection_index += 1,
-                        _ => {},
-                    }
-                },
-                None => {},
+            if item.main_tag.is_some() {
+                item.global_state = Some(global_state.clone());
             }
+            report.items.push(item);
         }
 
-        report
+        Ok(report)
     }
 }
 
@@ -767,6 +1300,23 @@ pub mod name {
         }
     }
 
+    /// Finds a usage page by the name [`get_usage_page_name`] gives it, ignoring case.
+    pub fn find_usage_page(name: &str) -> Option<u32> {
+        let name = name.trim();
+        (0..=0xFFFF).find(|usage_page| get_usage_page_name(*usage_page).trim().eq_ignore_ascii_case(name))
+    }
+
+    /// Finds a usage of a usage page by the name [`get_usage_name`] gives it, ignoring case.
+    ///
+    /// Reserved usages share their name, so they can't be found.
+    pub fn find_usage(usage_page: u32, name: &str) -> Option<u32> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("Reserved") {
+            return None;
+        }
+        (0..=0xFFFF).find(|usage| get_usage_name(Some(usage_page), *usage).trim().eq_ignore_ascii_case(name))
+    }
+
     fn get_generic_desktop_usage_name(usage: u32) -> String {
         match usage {
             0x00 => "Undefined".to_string(),
@@ -2152,15 +2702,11 @@ pub mod name {
     }
 
     fn get_physical_interface_device_usage_name(usage: u32) -> String {
-        match usage {
-            _ => format!("{:#04X}", usage),
-        }
+        format!("{:#04X}", usage)
     }
 
     fn get_unicode_usage_name(usage: u32) -> String {
-        match usage {
-            _ => format!("{:#04X}", usage),
-        }
+        format!("{:#04X}", usage)
     }
 
     fn get_eye_and_head_trackers_usage_name(usage: u32) -> String {
@@ -3223,16 +3769,12 @@ pub mod name {
 
     fn get_monitor_usage_name(usage_page: u32, usage: u32) -> String {
         // 0x80..=0x83
-        match usage {
-            _ => format!("{:#04X}-{:#04X}", usage_page, usage),
-        }
+        format!("{:#04X}-{:#04X}", usage_page, usage)
     }
 
     fn get_power_usage_name(usage_page: u32, usage: u32) -> String {
         // 0x84..=0x87
-        match usage {
-            _ => format!("{:#04X}-{:#04X}", usage_page, usage),
-        }
+        format!("{:#04X}-{:#04X}", usage_page, usage)
     }
 
     fn get_bar_code_scanner_usage_name(usage: u32) -> String {
@@ -3356,7 +3898,7 @@ pub mod name {
             0xA0 => "UPC-A with P5 Optional".to_string(),
             0xA1 => "UPC-E".to_string(),
             0xA2 => "UPC-E1".to_string(),
-            0xA2..=0xA8 => "Reserved".to_string(),
+            0xA3..=0xA8 => "Reserved".to_string(),
             0xA9 => "Periodical".to_string(),
             0xAA => "Periodical Auto-Discriminate + 2".to_string(),
             0xAB => "Periodical Only Decode with + 2".to_string(),
@@ -3464,7 +4006,7 @@ pub mod name {
         match usage {
             0x00 => "Undefined".to_string(),
             0x01 => "Weighing Device".to_string(),
-            0x01..=0x1F => "Reserved".to_string(),
+            0x02..=0x1F => "Reserved".to_string(),
             0x20 => "Scale Device".to_string(),
             0x21 => "Scale Class I Metric".to_string(),
             0x22 => "Scale Class I Metric".to_string(),
@@ -3567,15 +4109,11 @@ pub mod name {
     }
 
     fn get_arcade_usage_name(usage: u32) -> String {
-        match usage {
-            _ => format!("{:#04X}", usage),
-        }
+        format!("{:#04X}", usage)
     }
 
     fn get_gaming_device_usage_name(usage: u32) -> String {
-        match usage {
-            _ => format!("{:#04X}", usage),
-        }
+        format!("{:#04X}", usage)
     }
 
     fn get_fido_alliance_usage_name(usage: u32) -> String {
@@ -3592,9 +4130,7 @@ pub mod name {
 
     fn get_vendor_defined_usage_name(_usage_page: u32, usage: u32) -> String {
         // 0xFF00..=0xFFFF
-        match usage {
-            _ => format!("{:#04X}", usage),
-        }
+        format!("{:#04X}", usage)
     }
 }
 
@@ -3883,5 +4419,169 @@ mod tests {
 
         assert!(matches!(&report.items[63].item_type, descriptor::HidItemType::Main));
         assert!(matches!(&report.items[63].main_tag, Some(descriptor::HidMainTag::EndCollection)));
+
+        assert_eq!(report.to_bytes(), bytes.to_vec());
+    }
+
+    #[test]
+    fn truncated_item_is_an_error() {
+        // Logical Maximum announces two data bytes, only one is present
+        let bytes = [0x05, 0x0B, 0x26, 0xFF];
+        let error = descriptor::try_get_descriptor_report(&bytes).unwrap_err();
+
+        assert_eq!(error, descriptor::ParseError::UnexpectedEnd { offset: 2, prefix: 0x26, expected: 2, available: 1 });
+        assert_eq!(error.offset(), 2);
+        assert_eq!(error.prefix(), 0x26);
+    }
+
+    #[test]
+    fn undefined_tags_are_errors() {
+        let error = descriptor::try_get_descriptor_report(&[0x05, 0x0B, 0x01, 0x00]).unwrap_err();
+        assert_eq!(error, descriptor::ParseError::InvalidMainTag { offset: 2, prefix: 0x01 });
+
+        let error = descriptor::try_get_descriptor_report(&[0x69, 0x00]).unwrap_err();
+        assert_eq!(error, descriptor::ParseError::InvalidLocalTag { offset: 0, prefix: 0x69 });
+    }
+
+    #[test]
+    fn zero_sized_main_items() {
+        let report = descriptor::try_get_descriptor_report(&[0xA0, 0x80, 0x90, 0xC0]).unwrap();
+
+        assert!(matches!(&report.items[0].main_tag, Some(descriptor::HidMainTag::Collection(descriptor::CollectionType::Physical))));
+        assert!(matches!(&report.items[1].main_tag, Some(descriptor::HidMainTag::Input(data)) if data.item_type == descriptor::ItemType::Data));
+        assert!(matches!(&report.items[2].main_tag, Some(descriptor::HidMainTag::Output(data)) if data.data_type == descriptor::DataType::Array));
+        assert!(matches!(&report.items[3].main_tag, Some(descriptor::HidMainTag::EndCollection)));
+    }
+
+    #[test]
+    fn long_items_are_skipped() {
+        // Long item with tag 0x42 and three data bytes between two short items
+        let bytes = [0x05, 0x0B, 0xFE, 0x03, 0x42, 0x01, 0x02, 0x03, 0x09, 0x05];
+        let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+
+        assert_eq!(report.items.len(), 3);
+        assert_eq!(report.items[1].item_type, descriptor::HidItemType::Long);
+        assert_eq!(report.items[1].size_bytes, 3);
+        assert_eq!(report.items[1].bytes, bytes[2..8].to_vec());
+        assert_eq!(report.items[1].long_item, Some(descriptor::HidLongItem { tag: 0x42, data: vec![0x01, 0x02, 0x03] }));
+        assert_eq!(report.items[1].to_string(), "Long Item (0x42, 3 bytes) [Long]");
+        assert!(matches!(&report.items[2].local_tag, Some(descriptor::HidLocalTag::Usage(0x05))));
+
+        let error = descriptor::try_get_descriptor_report(&[0xFE, 0x04, 0x42, 0x01]).unwrap_err();
+        assert_eq!(error, descriptor::ParseError::UnexpectedEnd { offset: 0, prefix: 0xFE, expected: 6, available: 3 });
+    }
+
+    #[test]
+    fn global_state_push_pop() {
+        let bytes = [
+            0x05, 0x0B, // Usage Page (Telephony Page)
+            0x09, 0x05, // Usage (Headset)
+            0xA1, 0x01, // Collection (Application)
+            0x15, 0x00, // Logical Minimum (0)
+            0x25, 0x01, // Logical Maximum (1)
+            0x75, 0x01, // Report Size (1)
+            0x95, 0x01, // Report Count (1)
+            0xA4,       // Push
+            0x05, 0x08, // Usage Page (LED Page)
+            0x09, 0x17, // Usage (Off-Hook)
+            0x91, 0x02, // Output (Data, Variable, Absolute)
+            0xB4,       // Pop
+            0x09, 0x20, // Usage (Hook Switch)
+            0x81, 0x02, // Input (Data, Variable, Absolute)
+            0xC0,       // End Collection
+        ];
+        let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+
+        let output_state = report.items[10].global_state.as_ref().unwrap();
+        assert_eq!(output_state.usage_page, Some(0x08));
+        assert_eq!(output_state.logical_maximum, Some(1));
+        assert_eq!(output_state.report_size, Some(1));
+
+        let input_state = report.items[13].global_state.as_ref().unwrap();
+        assert_eq!(input_state.usage_page, Some(0x0B));
+        assert_eq!(input_state.report_count, Some(1));
+        assert_eq!(report.items[12].to_string(), "Usage (Hook Switch) [Local]");
+
+        assert!(report.items[0].global_state.is_none());
+
+        let error = descriptor::try_get_descriptor_report(&[0x05, 0x0B, 0xB4]).unwrap_err();
+        assert_eq!(error, descriptor::ParseError::PopWithoutPush { offset: 2, prefix: 0xB4 });
+    }
+
+    #[test]
+    fn signed_extents() {
+        let bytes = [
+            0x15, 0x81,       // Logical Minimum (-127)
+            0x25, 0x7F,       // Logical Maximum (127)
+            0x35, 0x00,       // Physical Minimum (0)
+            0x46, 0xFF, 0x00, // Physical Maximum (255)
+            0x55, 0x0E,       // Unit Exponent (-2)
+            0x75, 0x08,       // Report Size (8)
+            0x95, 0x01,       // Report Count (1)
+            0x81, 0x02,       // Input (Data, Variable, Absolute)
+            0x15, 0x00,       // Logical Minimum (0)
+            0x25, 0xFF,       // Logical Maximum (255 when read unsigned)
+            0x81, 0x02,       // Input (Data, Variable, Absolute)
+        ];
+        let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+
+        assert!(matches!(&report.items[0].global_tag, Some(descriptor::HidGlobalTag::LogicalMinimum(-127))));
+        assert!(matches!(&report.items[3].global_tag, Some(descriptor::HidGlobalTag::PhysicalMaximum(255))));
+        assert!(matches!(&report.items[4].global_tag, Some(descriptor::HidGlobalTag::UnitExponent(-2))));
+        assert!(matches!(&report.items[9].global_tag, Some(descriptor::HidGlobalTag::LogicalMaximum(-1))));
+
+        let state = report.items[7].global_state.as_ref().unwrap();
+        assert_eq!(state.logical_extents(), Some((-127, 127)));
+        assert_eq!(state.physical_extents(), Some((0, 255)));
+
+        let state = report.items[10].global_state.as_ref().unwrap();
+        assert_eq!(state.logical_extents(), Some((0, 255)));
+    }
+
+    #[test]
+    fn extended_usages() {
+        let bytes = [
+            0x05, 0x0B,                   // Usage Page (Telephony Page)
+            0x09, 0x20,                   // Usage (Hook Switch)
+            0x0B, 0xE9, 0x00, 0x0C, 0x00, // Usage (Consumer Page: Volume Increment)
+            0x19, 0x01,                   // Usage Minimum (Phone)
+        ];
+        let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+
+        assert_eq!(report.items[1].usage, Some(descriptor::Usage::new(0x0B, 0x20)));
+        assert_eq!(report.items[1].to_string(), "Usage (Hook Switch) [Local]");
+
+        assert_eq!(report.items[2].usage, Some(descriptor::Usage::new(0x0C, 0xE9)));
+        assert!(report.items[2].is_extended_usage());
+        assert!(!report.items[1].is_extended_usage());
+        assert_eq!(report.items[2].to_string(), "Usage (Consumer Page: Volume Increment) [Local]");
+
+        assert_eq!(report.items[3].usage, Some(descriptor::Usage::new(0x0B, 0x01)));
+        assert_eq!(report.items[3].to_string(), "Usage Minimum (Phone) [Local]");
+        assert_eq!(report.items[0].usage, None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_json_shape() {
+        let bytes = [
+            0x05, 0x0B, // Usage Page (Telephony Page)
+            0x09, 0x20, // Usage (Hook Switch)
+            0x81, 0x02, // Input (Data, Variable, Absolute)
+        ];
+        let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+        let json = serde_json::to_value(&report).unwrap();
+
+        assert_eq!(json["items"][0]["item_type"], "Global");
+        assert_eq!(json["items"][0]["global_tag"], serde_json::json!({ "UsagePage": 11 }));
+        assert_eq!(json["items"][1]["text"], "Usage (Hook Switch)");
+        assert_eq!(
+            json["items"][1]["usage"],
+            serde_json::json!({ "page": 11, "id": 32, "page_name": "Telephony Page", "name": "Hook Switch" })
+        );
+        assert_eq!(json["items"][2]["main_tag"]["Input"]["data_type"], "Variable");
+
+        let parsed: descriptor::Report = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.to_bytes(), bytes.to_vec());
     }
 }
\ No newline at end of file
@@ -0,0 +1,722 @@
+use std::fmt;
+
+use super::descriptor::{CollectionType, HidGlobalTag, HidLocalTag, HidMainTag, MainInputData, MainOutputData};
+use super::formatter::ELECCELERATOR_USAGE_PAGES;
+use super::name;
+use super::serializer::{encode_global_tag, encode_local_tag, encode_main_tag, SerializeError};
+
+const ITEM_NAMES: [&str; 27] = [
+    "Input",
+    "Output",
+    "Feature",
+    "Collection",
+    "End Collection",
+    "Usage Page",
+    "Logical Minimum",
+    "Logical Maximum",
+    "Physical Minimum",
+    "Physical Maximum",
+    "Unit Exponent",
+    "Unit",
+    "Report Size",
+    "Report ID",
+    "Report Count",
+    "Push",
+    "Pop",
+    "Usage",
+    "Usage Minimum",
+    "Usage Maximum",
+    "Designator Index",
+    "Designator Minimum",
+    "Designator Maximum",
+    "String Index",
+    "String Minimum",
+    "String Maximum",
+    "Delimiter",
+];
+
+// Usage page names used by the eleccelerator parser and hidrd-convert
+const USAGE_PAGE_ALIASES: [(&str, u32); 20] = [
+    ("Generic Desktop Ctrls", 0x01),
+    ("Generic Desktop", 0x01),
+    ("Desktop", 0x01),
+    ("Simulation Ctrls", 0x02),
+    ("Simulation", 0x02),
+    ("VR Ctrls", 0x03),
+    ("Sport Ctrls", 0x04),
+    ("Game Ctrls", 0x05),
+    ("Generic Dev Ctrls", 0x06),
+    ("Generic Device", 0x06),
+    ("Kbrd/Keypad", 0x07),
+    ("Keyboard", 0x07),
+    ("LEDs", 0x08),
+    ("Digitizer", 0x0D),
+    ("PID", 0x0F),
+    ("Physical Interface Device", 0x0F),
+    ("Medical Instruments", 0x40),
+    ("Medical", 0x40),
+    ("Bar Code", 0x8C),
+    ("Camera Control", 0x90),
+];
+
+/// Error returned by [`assemble`], with the 1-based line and column it was found at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssembleError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: AssembleErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssembleErrorKind {
+    /// The text isn't the name of an item.
+    UnknownItem(String),
+    /// The item needs a value in parentheses.
+    MissingValue(String),
+    /// A parenthesis or comment isn't closed.
+    Unclosed(char),
+    UnexpectedCharacter(char),
+    /// A byte in a hex line isn't written as `0x` and one or two hex digits.
+    InvalidByte(String),
+    InvalidNumber(String),
+    OutOfRange { value: i64, minimum: i64, maximum: i64 },
+    UnknownUsagePage(String),
+    /// The name is shared by several usage pages, like `Monitor Pages`, so the page has to be a number.
+    AmbiguousUsagePage(String),
+    UnknownUsage(String),
+    /// A usage is given by name before any Usage Page.
+    MissingUsagePage(String),
+    UnknownFlag(String),
+    UnknownCollectionType(String),
+    PopWithoutPush,
+    Serialize(SerializeError),
+}
+
+impl fmt::Display for AssembleErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssembleErrorKind::UnknownItem(text) => write!(f, "Unknown item `{}`", text),
+            AssembleErrorKind::MissingValue(item) => write!(f, "`{}` needs a value in parentheses", item),
+            AssembleErrorKind::Unclosed(c) => write!(f, "Unclosed `{}`", c),
+            AssembleErrorKind::UnexpectedCharacter(c) => write!(f, "Unexpected `{}`", c),
+            AssembleErrorKind::InvalidByte(text) => write!(f, "`{}` is not a hex byte", text),
+            AssembleErrorKind::InvalidNumber(text) => write!(f, "Expected a number, found `{}`", text),
+            AssembleErrorKind::OutOfRange { value, minimum, maximum } => {
+                write!(f, "{} is outside of {} to {}", value, minimum, maximum)
+            },
+            AssembleErrorKind::UnknownUsagePage(text) => write!(f, "Unknown usage page `{}`", text),
+            AssembleErrorKind::AmbiguousUsagePage(text) => write!(f, "`{}` names several usage pages", text),
+            AssembleErrorKind::UnknownUsage(text) => write!(f, "Unknown usage `{}`", text),
+            AssembleErrorKind::MissingUsagePage(text) => write!(f, "Usage `{}` needs a Usage Page before it", text),
+            AssembleErrorKind::UnknownFlag(text) => write!(f, "Unknown flag `{}`", text),
+            AssembleErrorKind::UnknownCollectionType(text) => write!(f, "Unknown collection type `{}`", text),
+            AssembleErrorKind::PopWithoutPush => write!(f, "Pop without a matching Push"),
+            AssembleErrorKind::Serialize(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.kind)
+    }
+}
+
+impl std::error::Error for AssembleError {}
+
+// A 0-based column and what went wrong there
+type LineError = (usize, AssembleErrorKind);
+
+/// Assembles report descriptor bytes from text.
+///
+/// Every line is either items, as [`HidItem`](super::descriptor::HidItem) prints them or in the
+/// hidrd-convert spec style, or hex bytes, as the eleccelerator parser and hidrd-convert code
+/// output write them:
+///
+/// ```text
+/// Usage Page (Telephony Page) [Global]
+/// 0x09, 0x05,        // Usage (Headset)
+/// Collection (Application),
+/// End Collection
+/// ```
+///
+/// Values are names or numbers, in decimal, `0x` hex or `h` suffixed hex. `//` and `/* */`
+/// comments are ignored.
+pub fn assemble(text: &str) -> Result<Vec<u8>, AssembleError> {
+    let mut assembler = Assembler::default();
+    let mut in_comment = false;
+
+    for (line_index, line) in text.lines().enumerate() {
+        let chars = strip_comments(line, &mut in_comment);
+        assembler.assemble_line(&chars).map_err(|(column, kind)| AssembleError {
+            line: line_index + 1,
+            column: column + 1,
+            kind,
+        })?;
+    }
+
+    if in_comment {
+        return Err(AssembleError {
+            line: text.lines().count().max(1),
+            column: 1,
+            kind: AssembleErrorKind::Unclosed('/'),
+        });
+    }
+
+    Ok(assembler.bytes)
+}
+
+// Blanks out comments, so the columns of the rest of the line are kept
+pub(crate) fn strip_comments(line: &str, in_comment: &mut bool) -> Vec<char> {
+    let mut chars: Vec<char> = line.chars().collect();
+    let mut index = 0;
+
+    while index < chars.len() {
+        let next = chars.get(index + 1).copied();
+        if *in_comment {
+            if chars[index] == '*' && next == Some('/') {
+                *in_comment = false;
+                chars[index + 1] = ' ';
+            }
+            chars[index] = ' ';
+        } else if chars[index] == '/' && next == Some('/') {
+            chars[index..].iter_mut().for_each(|c| *c = ' ');
+            break;
+        } else if chars[index] == '/' && next == Some('*') {
+            *in_comment = true;
+            chars[index] = ' ';
+            chars[index + 1] = ' ';
+            index += 1;
+        }
+        index += 1;
+    }
+
+    chars
+}
+
+#[derive(Default)]
+struct Assembler {
+    bytes: Vec<u8>,
+    // The usage page that names of usages are looked up on
+    usage_page: Option<u32>,
+    stack: Vec<Option<u32>>,
+}
+
+impl Assembler {
+    fn assemble_line(&mut self, chars: &[char]) -> Result<(), LineError> {
+        let start = skip_whitespace(chars, 0);
+        if start == chars.len() {
+            return Ok(());
+        }
+
+        if chars[start] == '0' && matches!(chars.get(start + 1), Some('x' | 'X')) {
+            self.assemble_hex(chars, start)
+        } else {
+            self.assemble_items(chars, start)
+        }
+    }
+
+    fn assemble_hex(&mut self, chars: &[char], mut index: usize) -> Result<(), LineError> {
+        while index < chars.len() {
+            if chars[index].is_whitespace() || chars[index] == ',' {
+                index += 1;
+                continue;
+            }
+
+            let start = index;
+            while index < chars.len() && !chars[index].is_whitespace() && chars[index] != ',' {
+                index += 1;
+            }
+            let text: String = chars[start..index].iter().collect();
+            let byte = text
+                .strip_prefix("0x")
+                .or_else(|| text.strip_prefix("0X"))
+                .filter(|digits| (1..=2).contains(&digits.len()))
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok());
+            match byte {
+                Some(byte) => self.bytes.push(byte),
+                None => return Err((start, AssembleErrorKind::InvalidByte(text))),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn assemble_items(&mut self, chars: &[char], mut index: usize) -> Result<(), LineError> {
+        loop {
+            index = skip_whitespace(chars, index);
+            while index < chars.len() && matches!(chars[index], ',' | ';') {
+                index = skip_whitespace(chars, index + 1);
+            }
+            if index == chars.len() {
+                return Ok(());
+            }
+
+            let mut words: Vec<(String, usize)> = vec![];
+            while index < chars.len() && chars[index].is_alphabetic() {
+                let start = index;
+                while index < chars.len() && chars[index].is_alphabetic() {
+                    index += 1;
+                }
+                words.push((chars[start..index].iter().collect(), start));
+                index = skip_whitespace(chars, index);
+            }
+            if words.is_empty() {
+                return Err((index, AssembleErrorKind::UnexpectedCharacter(chars[index])));
+            }
+
+            let argument = if index < chars.len() && chars[index] == '(' {
+                let end = find_closing_parenthesis(chars, index).ok_or((index, AssembleErrorKind::Unclosed('(')))?;
+                let start = skip_whitespace(chars, index + 1);
+                let text: String = chars[start.min(end)..end].iter().collect();
+                index = end + 1;
+                Some((text.trim_end().to_string(), start.min(end)))
+            } else {
+                None
+            };
+
+            // A run of names is several items when only the last one takes an argument
+            let names = split_item_names(&words)?;
+            for (position, (name, column)) in names.iter().enumerate() {
+                let item_argument = if position == names.len() - 1 { argument.as_ref() } else { None };
+                let bytes = self.assemble_item(name, *column, item_argument)?;
+                self.bytes.extend(bytes);
+            }
+
+            // The item type, as in `[Global]`, is optional
+            index = skip_whitespace(chars, index);
+            if index < chars.len() && chars[index] == '[' {
+                match chars[index..].iter().position(|c| *c == ']') {
+                    Some(offset) => index += offset + 1,
+                    None => return Err((index, AssembleErrorKind::Unclosed('['))),
+                }
+            }
+        }
+    }
+
+    fn assemble_item(&mut self, name: &str, column: usize, argument: Option<&(String, usize)>) -> Result<Vec<u8>, LineError> {
+        let (text, value_column) = match (name, argument) {
+            ("End Collection", None) => return encode(column, encode_main_tag(&HidMainTag::EndCollection, None)),
+            ("Push", None) => {
+                self.stack.push(self.usage_page);
+                return encode(column, encode_global_tag(&HidGlobalTag::Push(0), None));
+            },
+            ("Pop", None) => return self.pop(column, 0),
+            (_, None) => return Err((column, AssembleErrorKind::MissingValue(name.to_string()))),
+            (_, Some((text, value_column))) => (unquote(text), *value_column),
+        };
+
+        let value = |kind: AssembleErrorKind| (value_column, kind);
+        let int = |minimum: i64, maximum: i64| -> Result<i64, LineError> {
+            match parse_int(text) {
+                Some(v) if (minimum..=maximum).contains(&v) => Ok(v),
+                Some(v) => Err(value(AssembleErrorKind::OutOfRange { value: v, minimum, maximum })),
+                None => Err(value(AssembleErrorKind::InvalidNumber(text.to_string()))),
+            }
+        };
+        let signed = || int(i32::MIN as i64, i32::MAX as i64).map(|v| v as i32);
+        let unsigned = || int(0, u32::MAX as i64).map(|v| v as u32);
+
+        let result = match name {
+            "Input" => {
+                let bits = parse_main_data(text, false).map_err(value)?;
+                encode_main_tag(&HidMainTag::Input(MainInputData::from_bits(bits)), None)
+            },
+            "Output" => {
+                let bits = parse_main_data(text, true).map_err(value)?;
+                encode_main_tag(&HidMainTag::Output(MainOutputData::from_bits(bits)), None)
+            },
+            "Feature" => {
+                let bits = parse_main_data(text, true).map_err(value)?;
+                encode_main_tag(&HidMainTag::Feature(MainOutputData::from_bits(bits)), None)
+            },
+            "Collection" => {
+                let collection_type = parse_collection_type(text)
+                    .ok_or_else(|| value(AssembleErrorKind::UnknownCollectionType(text.to_string())))?;
+                encode_main_tag(&HidMainTag::Collection(collection_type), None)
+            },
+            "End Collection" => encode_main_tag(&HidMainTag::EndCollection, None),
+            "Usage Page" => {
+                let usage_page = match parse_int(text) {
+                    Some(_) => int(0, 0xFFFF)? as u32,
+                    None => find_usage_page(text).map_err(value)?,
+                };
+                self.usage_page = Some(usage_page);
+                encode_global_tag(&HidGlobalTag::UsagePage(usage_page), None)
+            },
+            "Logical Minimum" => encode_global_tag(&HidGlobalTag::LogicalMinimum(signed()?), None),
+            "Logical Maximum" => encode_global_tag(&HidGlobalTag::LogicalMaximum(signed()?), None),
+            "Physical Minimum" => encode_global_tag(&HidGlobalTag::PhysicalMinimum(signed()?), None),
+            "Physical Maximum" => encode_global_tag(&HidGlobalTag::PhysicalMaximum(signed()?), None),
+            "Unit Exponent" => encode_global_tag(&HidGlobalTag::UnitExponent(signed()?), None),
+            // HidItem prints Unit signed
+            "Unit" => encode_global_tag(&HidGlobalTag::Unit(int(i32::MIN as i64, u32::MAX as i64)? as i32), None),
+            "Report Size" => encode_global_tag(&HidGlobalTag::ReportSize(unsigned()?), None),
+            "Report ID" => encode_global_tag(&HidGlobalTag::ReportId(int(1, 0xFF)? as u32), None),
+            "Report Count" => encode_global_tag(&HidGlobalTag::ReportCount(unsigned()?), None),
+            "Push" => {
+                self.stack.push(self.usage_page);
+                encode_global_tag(&HidGlobalTag::Push(signed()?), None)
+            },
+            "Pop" => return self.pop(column, signed()?),
+            "Usage" => encode_local_tag(&HidLocalTag::Usage(self.parse_usage(text).map_err(value)?), None),
+            "Usage Minimum" => encode_local_tag(&HidLocalTag::UsageMinimum(self.parse_usage(text).map_err(value)?), None),
+            "Usage Maximum" => encode_local_tag(&HidLocalTag::UsageMaximum(self.parse_usage(text).map_err(value)?), None),
+            "Designator Index" => encode_local_tag(&HidLocalTag::DesignatorIndex(unsigned()?), None),
+            "Designator Minimum" => encode_local_tag(&HidLocalTag::DesignatorMinimum(unsigned()?), None),
+            "Designator Maximum" => encode_local_tag(&HidLocalTag::DesignatorMaximum(unsigned()?), None),
+            "String Index" => encode_local_tag(&HidLocalTag::StringIndex(unsigned()?), None),
+            "String Minimum" => encode_local_tag(&HidLocalTag::StringMinimum(unsigned()?), None),
+            "String Maximum" => encode_local_tag(&HidLocalTag::StringMaximum(unsigned()?), None),
+            "Delimiter" => encode_local_tag(&HidLocalTag::Delimiter(unsigned()?), None),
+            _ => unreachable!("Every name in ITEM_NAMES is handled"),
+        };
+
+        encode(column, result)
+    }
+
+    fn pop(&mut self, column: usize, value: i32) -> Result<Vec<u8>, LineError> {
+        match self.stack.pop() {
+            Some(usage_page) => self.usage_page = usage_page,
+            None => return Err((column, AssembleErrorKind::PopWithoutPush)),
+        }
+        encode(column, encode_global_tag(&HidGlobalTag::Pop(value), None))
+    }
+
+    fn parse_usage(&self, text: &str) -> Result<u32, AssembleErrorKind> {
+        // An extended usage names its usage page
+        if let Some((page_name, usage_name)) = text.split_once(':') {
+            let usage_page = match find_usage_page(page_name) {
+                Ok(usage_page) => Some(usage_page),
+                Err(AssembleErrorKind::AmbiguousUsagePage(text)) => return Err(AssembleErrorKind::AmbiguousUsagePage(text)),
+                Err(_) => None,
+            };
+            if let Some(usage_page) = usage_page {
+                let usage = match parse_int(usage_name) {
+                    Some(usage) => u16::try_from(usage).ok().map(u32::from),
+                    None => name::find_usage(usage_page, usage_name),
+                };
+                return usage
+                    .map(|usage| usage_page << 16 | usage)
+                    .ok_or_else(|| AssembleErrorKind::UnknownUsage(text.to_string()));
+            }
+        }
+
+        if let Some(usage) = parse_int(text) {
+            return u32::try_from(usage).map_err(|_| AssembleErrorKind::OutOfRange {
+                value: usage,
+                minimum: 0,
+                maximum: u32::MAX as i64,
+            });
+        }
+
+        match self.usage_page {
+            Some(usage_page) => {
+                name::find_usage(usage_page, text).ok_or_else(|| AssembleErrorKind::UnknownUsage(text.to_string()))
+            },
+            None => Err(AssembleErrorKind::MissingUsagePage(text.to_string())),
+        }
+    }
+}
+
+fn encode(column: usize, result: Result<Vec<u8>, SerializeError>) -> Result<Vec<u8>, LineError> {
+    result.map_err(|error| (column, AssembleErrorKind::Serialize(error)))
+}
+
+fn skip_whitespace(chars: &[char], mut index: usize) -> usize {
+    while index < chars.len() && chars[index].is_whitespace() {
+        index += 1;
+    }
+    index
+}
+
+// Names of usages can hold parentheses, as in `Button 1 (primary/trigger)`
+fn find_closing_parenthesis(chars: &[char], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_quotes = false;
+
+    for (index, c) in chars.iter().enumerate().skip(open) {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '(' if !in_quotes => depth += 1,
+            ')' if !in_quotes => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    None
+}
+
+fn split_item_names(words: &[(String, usize)]) -> Result<Vec<(&'static str, usize)>, LineError> {
+    let mut names = vec![];
+    let mut start = 0;
+
+    while start < words.len() {
+        let longest = (start + 1..=words.len()).rev().find_map(|end| {
+            let name = words[start..end].iter().map(|(word, _)| word.as_str()).collect::<Vec<&str>>().join(" ");
+            ITEM_NAMES
+                .iter()
+                .find(|item_name| item_name.eq_ignore_ascii_case(&name))
+                .map(|item_name| (*item_name, end))
+        });
+
+        match longest {
+            Some((name, end)) => {
+                names.push((name, words[start].1));
+                start = end;
+            },
+            None => {
+                let text = words[start..].iter().map(|(word, _)| word.as_str()).collect::<Vec<&str>>().join(" ");
+                return Err((words[start].1, AssembleErrorKind::UnknownItem(text)));
+            },
+        }
+    }
+
+    Ok(names)
+}
+
+// A quoted value holds names with any characters
+fn unquote(text: &str) -> &str {
+    text.strip_prefix('"').and_then(|text| text.strip_suffix('"')).unwrap_or(text)
+}
+
+fn parse_int(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits.trim_start()),
+        None => (false, text),
+    };
+
+    let hex = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+        .or_else(|| digits.strip_suffix('h').filter(|hex| hex.starts_with(|c: char| c.is_ascii_digit())));
+    let value = match hex {
+        Some(hex) => i64::from_str_radix(hex, 16).ok()?,
+        None => digits.parse::<i64>().ok()?,
+    };
+
+    Some(if negative { -value } else { value })
+}
+
+fn find_usage_page(text: &str) -> Result<u32, AssembleErrorKind> {
+    let text = text.trim();
+    if let Some(usage_page) = USAGE_PAGE_ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(text))
+        .map(|(_, usage_page)| *usage_page)
+    {
+        return Ok(usage_page);
+    }
+    if let Some((usage_page, _)) = ELECCELERATOR_USAGE_PAGES
+        .iter()
+        .find(|(_, page_name)| page_name.eq_ignore_ascii_case(text))
+    {
+        return Ok(*usage_page);
+    }
+
+    // `Vendor-defined 0xFF00`, or `Vendor Defined 0xFF00` as the eleccelerator parser writes it
+    let lowercase = text.to_ascii_lowercase();
+    if let Some(number) = lowercase.strip_prefix("vendor-defined").or_else(|| lowercase.strip_prefix("vendor defined")) {
+        return parse_int(number)
+            .and_then(|usage_page| u32::try_from(usage_page).ok())
+            .ok_or_else(|| AssembleErrorKind::UnknownUsagePage(text.to_string()));
+    }
+
+    let usage_page = name::find_usage_page(text)
+        .or_else(|| name::find_usage_page(&format!("{} Page", text)))
+        .ok_or_else(|| AssembleErrorKind::UnknownUsagePage(text.to_string()))?;
+    // A name shared by a range of pages, like `Power Pages`, is found at the start of the range
+    if name::get_usage_page_name(usage_page + 1) == name::get_usage_page_name(usage_page) {
+        return Err(AssembleErrorKind::AmbiguousUsagePage(text.to_string()));
+    }
+    Ok(usage_page)
+}
+
+fn parse_main_data(text: &str, has_volatility: bool) -> Result<u32, AssembleErrorKind> {
+    if let Some(bits) = parse_int(text) {
+        return u32::try_from(bits).map_err(|_| AssembleErrorKind::OutOfRange {
+            value: bits,
+            minimum: 0,
+            maximum: u32::MAX as i64,
+        });
+    }
+
+    let mut bits = 0;
+    for flag in text.split(',') {
+        // Spelling varies, as in `Non-linear`, `Nonlinear` or `Buffered Bytes`
+        let normalized: String = flag
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-')
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        let bit = match normalized.as_str() {
+            "data" | "array" | "arr" | "absolute" | "abs" | "nowrap" | "linear" | "preferredstate" | "nonullstate"
+            | "nonullposition" | "bitfield" => None,
+            "nonvolatile" if has_volatility => None,
+            "constant" | "const" | "cnst" => Some(0),
+            "variable" | "var" => Some(1),
+            "relative" | "rel" => Some(2),
+            "wrap" => Some(3),
+            "nonlinear" | "nonliniear" => Some(4),
+            "nopreferredstate" | "nopreferred" => Some(5),
+            "nullstate" | "nullposition" => Some(6),
+            "volatile" if has_volatility => Some(7),
+            "bufferedbytes" | "buffered" => Some(8),
+            _ => return Err(AssembleErrorKind::UnknownFlag(flag.trim().to_string())),
+        };
+        if let Some(bit) = bit {
+            bits |= 1 << bit;
+        }
+    }
+
+    Ok(bits)
+}
+
+fn parse_collection_type(text: &str) -> Option<CollectionType> {
+    let collection_types = [
+        CollectionType::Physical,
+        CollectionType::Application,
+        CollectionType::Logical,
+        CollectionType::Report,
+        CollectionType::NamedArray,
+        CollectionType::UsageSwitch,
+        CollectionType::UsageModifier,
+    ];
+
+    // `Vendor Defined 0x80` as HidItem prints it, or just the number
+    let lowercase = text.trim().to_ascii_lowercase();
+    let number = lowercase.strip_prefix("vendor defined").unwrap_or(&lowercase);
+    match parse_int(number) {
+        Some(value @ 0x80..=0xFF) => Some(CollectionType::VendorDefined(value as u8)),
+        Some(value) => collection_types.into_iter().find(|c| c.to_value().map(i64::from) == Some(value)),
+        None => collection_types.into_iter().find(|c| c.to_string().eq_ignore_ascii_case(text.trim())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assemble, AssembleError, AssembleErrorKind};
+    use crate::hid::descriptor;
+
+    const HEADSET: [u8; 40] = [
+        0x05, 0x0B,                   // Usage Page (Telephony Page)
+        0x09, 0x05,                   // Usage (Headset)
+        0xA1, 0x01,                   // Collection (Application)
+        0x85, 0x02,                   // Report ID (2)
+        0x15, 0x00,                   // Logical Minimum (0)
+        0x25, 0x01,                   // Logical Maximum (1)
+        0x75, 0x01,                   // Report Size (1)
+        0x09, 0x20,                   // Usage (Hook Switch)
+        0x95, 0x01,                   // Report Count (1)
+        0x81, 0x02,                   // Input (Data, Variable, Absolute)
+        0x95, 0x07,                   // Report Count (7)
+        0x81, 0x01,                   // Input (Constant, Array, Absolute)
+        0xA4,                         // Push
+        0x05, 0x08,                   // Usage Page (LED Page)
+        0x09, 0x17,                   // Usage (Off-Hook)
+        0x95, 0x01,                   // Report Count (1)
+        0x91, 0x22,                   // Output (Data, Variable, Absolute, No Preferred State)
+        0xB4,                         // Pop
+        0x0B, 0xE9, 0x00, 0x0C, 0x00, // Usage (Consumer Page: Volume Increment)
+        0xC0,                         // End Collection
+    ];
+
+    #[test]
+    fn assemble_printed_items() {
+        let report = descriptor::try_get_descriptor_report(&HEADSET).unwrap();
+        let text = report.items.iter().map(|item| item.to_string()).collect::<Vec<String>>().join("\n");
+
+        assert_eq!(assemble(&text).unwrap(), HEADSET.to_vec());
+    }
+
+    #[test]
+    fn assemble_other_styles() {
+        let eleccelerator = "
+            0x05, 0x0B,        // Usage Page (Telephony)
+            0x09, 0x05,        // Usage (Headset)
+            0xA1, 0x01,        // Collection (Application)
+            0x85, 0x02,        //   Report ID (2)
+            0x15, 0x00,        //   Logical Minimum (0)
+            0x25, 0x01,        //   Logical Maximum (1)
+            0x75, 0x01,        //   Report Size (1)
+            0x09, 0x20,        //   Usage (Hook Switch)
+            0x95, 0x01,        //   Report Count (1)
+            0x81, 0x02,        //   Input (Data,Var,Abs,No Wrap,Linear,Preferred State,No Null Position)
+            0x95, 0x07,        //   Report Count (7)
+            0x81, 0x01,        //   Input (Const,Array,Abs,No Wrap,Linear,Preferred State,No Null Position)
+            0xA4,              //   Push
+            0x05, 0x08,        //   Usage Page (LEDs)
+            0x09, 0x17,        //   Usage (Off-Hook)
+            0x95, 0x01,        //   Report Count (1)
+            0x91, 0x22,        //   Output (Data,Var,Abs,No Wrap,Linear,No Preferred State,No Null Position,Non-volatile)
+            0xB4,              //   Pop
+            0x0B, 0xE9, 0x00, 0x0C, 0x00,  //   Usage (0x0C00E9)
+            0xC0,              // End Collection
+        ";
+        assert_eq!(assemble(eleccelerator).unwrap(), HEADSET.to_vec());
+
+        let hidrd = "
+            Usage Page (Telephony),
+            Usage (Headset),
+            Collection (Application),
+                Report ID (2),
+                Logical Minimum (0),
+                Logical Maximum (1),
+                Report Size (1),
+                Usage (Hook Switch),
+                Report Count (1),
+                Input (Variable),
+                Report Count (7),
+                Input (Constant),
+                Push,
+                Usage Page (LED),
+                Usage (Off-Hook),
+                Report Count (1),
+                Output (Variable, No Preferred),
+                Pop,
+                Usage (Consumer: 0E9h),
+            End Collection
+        ";
+        assert_eq!(assemble(hidrd).unwrap(), HEADSET.to_vec());
+    }
+
+    #[test]
+    fn assemble_errors() {
+        let error = assemble("Usage Page (Telephony Page)\n  Usage (Hook Swtich)").unwrap_err();
+        assert_eq!(
+            error,
+            AssembleError { line: 2, column: 10, kind: AssembleErrorKind::UnknownUsage("Hook Swtich".to_string()) }
+        );
+        assert_eq!(error.to_string(), "line 2, column 10: Unknown usage `Hook Swtich`");
+
+        let error = assemble("Report Size (8)\nReport Cont (1)").unwrap_err();
+        assert_eq!(error.kind, AssembleErrorKind::UnknownItem("Report Cont".to_string()));
+        assert_eq!((error.line, error.column), (2, 1));
+
+        let error = assemble("0x05, 0x0B,\n0x09, 0x105").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 7));
+
+        // Pages 0x84 to 0x87 are all `Power Pages`
+        let error = assemble("Usage Page (Power Pages)").unwrap_err();
+        assert_eq!(error.kind, AssembleErrorKind::AmbiguousUsagePage("Power Pages".to_string()));
+        assert_eq!(assemble("Usage Page (0x85)").unwrap(), vec![0x05, 0x85]);
+    }
+
+    #[test]
+    fn assemble_vendor_collection() {
+        let bytes = [0xA1, 0x80, 0xC0, 0xA1, 0xFF, 0xC0];
+        let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+        let text: String = report.items.iter().map(|item| format!("{}\n", item)).collect();
+
+        assert!(text.starts_with("Collection (Vendor Defined 0x80)"));
+        assert_eq!(assemble(&text).unwrap(), bytes.to_vec());
+        assert_eq!(assemble("Collection (0xFF)\nEnd Collection").unwrap(), vec![0xA1, 0xFF, 0xC0]);
+    }
+}
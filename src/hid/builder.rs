@@ -0,0 +1,366 @@
+use std::fmt;
+
+use super::descriptor::{CollectionType, HidGlobalTag, HidLocalTag, HidMainTag, MainInputData, MainOutputData};
+use super::serializer::{encode_global_tag, encode_local_tag, encode_main_tag, SerializeError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// A main item is added before Report Size was set.
+    MissingReportSize,
+    /// A main item is added before Report Count was set.
+    MissingReportCount,
+    /// The Minimum of a range is larger than its Maximum.
+    InvalidRange { minimum: i32, maximum: i32 },
+    /// The Logical Minimum and Maximum don't fit in Report Size bits.
+    RangeExceedsReportSize { minimum: i32, maximum: i32, report_size: u32 },
+    /// Report ID 0 is reserved.
+    InvalidReportId,
+    /// A Pop item without a matching Push item.
+    PopWithoutPush,
+    /// A Push item without a matching Pop item.
+    PushWithoutPop,
+    Serialize(SerializeError),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::MissingReportSize => write!(f, "Report Size must be set before a main item"),
+            BuildError::MissingReportCount => write!(f, "Report Count must be set before a main item"),
+            BuildError::InvalidRange { minimum, maximum } => {
+                write!(f, "The minimum {} is larger than the maximum {}", minimum, maximum)
+            },
+            BuildError::RangeExceedsReportSize { minimum, maximum, report_size } => write!(
+                f,
+                "The logical range {} to {} doesn't fit in a Report Size of {}",
+                minimum, maximum, report_size
+            ),
+            BuildError::InvalidReportId => write!(f, "Report ID 0 is reserved"),
+            BuildError::PopWithoutPush => write!(f, "Pop without a matching Push"),
+            BuildError::PushWithoutPop => write!(f, "Push without a matching Pop"),
+            BuildError::Serialize(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+// The global items the build time checks need
+#[derive(Debug, Clone, Default)]
+struct BuilderState {
+    logical_minimum: Option<i32>,
+    logical_maximum: Option<i32>,
+    report_size: Option<u32>,
+    report_count: Option<u32>,
+}
+
+/// Builds a report descriptor item by item.
+///
+/// ```
+/// use usb_hid_descriptor_parser::hid::builder::DescriptorBuilder;
+/// use usb_hid_descriptor_parser::hid::descriptor::{CollectionType, DataType, MainInputData, UsagePage};
+///
+/// let variable = MainInputData { data_type: DataType::Variable, ..Default::default() };
+/// let bytes = DescriptorBuilder::new()
+///     .usage_page(UsagePage::Telephony)
+///     .usage(0x05)
+///     .collection(CollectionType::Application, |b| {
+///         b.usage(0x20).logical_range(0, 1).report_size(1).report_count(1).input(variable)
+///     })
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(bytes[..6], [0x05, 0x0B, 0x09, 0x05, 0xA1, 0x01]);
+/// ```
+#[derive(Debug, Default)]
+pub struct DescriptorBuilder {
+    bytes: Vec<u8>,
+    state: BuilderState,
+    stack: Vec<BuilderState>,
+    // The first error, returned by build
+    error: Option<BuildError>,
+}
+
+impl DescriptorBuilder {
+    pub fn new() -> Self {
+        DescriptorBuilder::default()
+    }
+
+    pub fn usage_page(self, usage_page: impl Into<u32>) -> Self {
+        self.global(HidGlobalTag::UsagePage(usage_page.into()))
+    }
+
+    pub fn logical_minimum(mut self, minimum: i32) -> Self {
+        self.state.logical_minimum = Some(minimum);
+        self.global(HidGlobalTag::LogicalMinimum(minimum))
+    }
+
+    pub fn logical_maximum(mut self, maximum: i32) -> Self {
+        self.state.logical_maximum = Some(maximum);
+        self.global(HidGlobalTag::LogicalMaximum(maximum))
+    }
+
+    pub fn logical_range(self, minimum: i32, maximum: i32) -> Self {
+        self.check_range(minimum, maximum)
+            .logical_minimum(minimum)
+            .logical_maximum(maximum)
+    }
+
+    pub fn physical_minimum(self, minimum: i32) -> Self {
+        self.global(HidGlobalTag::PhysicalMinimum(minimum))
+    }
+
+    pub fn physical_maximum(self, maximum: i32) -> Self {
+        self.global(HidGlobalTag::PhysicalMaximum(maximum))
+    }
+
+    pub fn physical_range(self, minimum: i32, maximum: i32) -> Self {
+        self.check_range(minimum, maximum)
+            .physical_minimum(minimum)
+            .physical_maximum(maximum)
+    }
+
+    pub fn unit_exponent(self, exponent: i32) -> Self {
+        self.global(HidGlobalTag::UnitExponent(exponent))
+    }
+
+    pub fn unit(self, unit: u32) -> Self {
+        self.global(HidGlobalTag::Unit(unit as i32))
+    }
+
+    pub fn report_size(mut self, size: u32) -> Self {
+        self.state.report_size = Some(size);
+        self.global(HidGlobalTag::ReportSize(size))
+    }
+
+    pub fn report_count(mut self, count: u32) -> Self {
+        self.state.report_count = Some(count);
+        self.global(HidGlobalTag::ReportCount(count))
+    }
+
+    pub fn report_id(mut self, report_id: u8) -> Self {
+        if report_id == 0 {
+            self.set_error(BuildError::InvalidReportId);
+        }
+        self.global(HidGlobalTag::ReportId(report_id as u32))
+    }
+
+    pub fn push(mut self) -> Self {
+        self.stack.push(self.state.clone());
+        self.global(HidGlobalTag::Push(0))
+    }
+
+    pub fn pop(mut self) -> Self {
+        match self.stack.pop() {
+            Some(state) => self.state = state,
+            None => self.set_error(BuildError::PopWithoutPush),
+        }
+        self.global(HidGlobalTag::Pop(0))
+    }
+
+    /// Adds a usage on the current usage page, or an extended usage if the page is in the high 16 bits.
+    pub fn usage(self, usage: u32) -> Self {
+        self.local(HidLocalTag::Usage(usage))
+    }
+
+    /// Adds a usage on another usage page than the current one.
+    pub fn extended_usage(self, usage_page: impl Into<u32>, usage: u16) -> Self {
+        self.usage(usage_page.into() << 16 | usage as u32)
+    }
+
+    pub fn usage_minimum(self, usage: u32) -> Self {
+        self.local(HidLocalTag::UsageMinimum(usage))
+    }
+
+    pub fn usage_maximum(self, usage: u32) -> Self {
+        self.local(HidLocalTag::UsageMaximum(usage))
+    }
+
+    pub fn usage_range(self, minimum: u32, maximum: u32) -> Self {
+        self.usage_minimum(minimum).usage_maximum(maximum)
+    }
+
+    pub fn designator_index(self, index: u32) -> Self {
+        self.local(HidLocalTag::DesignatorIndex(index))
+    }
+
+    pub fn string_index(self, index: u32) -> Self {
+        self.local(HidLocalTag::StringIndex(index))
+    }
+
+    pub fn input(self, data: MainInputData) -> Self {
+        self.check_field().main(HidMainTag::Input(data))
+    }
+
+    pub fn output(self, data: MainOutputData) -> Self {
+        self.check_field().main(HidMainTag::Output(data))
+    }
+
+    pub fn feature(self, data: MainOutputData) -> Self {
+        self.check_field().main(HidMainTag::Feature(data))
+    }
+
+    /// Adds a collection, with the items added by `f`, and its End Collection item.
+    pub fn collection(self, collection_type: CollectionType, f: impl FnOnce(Self) -> Self) -> Self {
+        let builder = self.main(HidMainTag::Collection(collection_type));
+        f(builder).main(HidMainTag::EndCollection)
+    }
+
+    /// Returns the descriptor bytes, or the first error found while building.
+    pub fn build(self) -> Result<Vec<u8>, BuildError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        if !self.stack.is_empty() {
+            return Err(BuildError::PushWithoutPop);
+        }
+
+        Ok(self.bytes)
+    }
+
+    fn main(mut self, tag: HidMainTag) -> Self {
+        let bytes = encode_main_tag(&tag, None);
+        self.extend(bytes);
+        self
+    }
+
+    fn global(mut self, tag: HidGlobalTag) -> Self {
+        let bytes = encode_global_tag(&tag, None);
+        self.extend(bytes);
+        self
+    }
+
+    fn local(mut self, tag: HidLocalTag) -> Self {
+        let bytes = encode_local_tag(&tag, None);
+        self.extend(bytes);
+        self
+    }
+
+    fn extend(&mut self, bytes: Result<Vec<u8>, SerializeError>) {
+        match bytes {
+            Ok(bytes) => self.bytes.extend(bytes),
+            Err(error) => self.set_error(BuildError::Serialize(error)),
+        }
+    }
+
+    fn set_error(&mut self, error: BuildError) {
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+    }
+
+    fn check_range(mut self, minimum: i32, maximum: i32) -> Self {
+        if minimum > maximum {
+            self.set_error(BuildError::InvalidRange { minimum, maximum });
+        }
+        self
+    }
+
+    fn check_field(mut self) -> Self {
+        let report_size = match self.state.report_size {
+            Some(report_size) => report_size,
+            None => {
+                self.set_error(BuildError::MissingReportSize);
+                return self;
+            },
+        };
+        if self.state.report_count.is_none() {
+            self.set_error(BuildError::MissingReportCount);
+        }
+
+        let minimum = self.state.logical_minimum.unwrap_or(0);
+        let maximum = self.state.logical_maximum.unwrap_or(0);
+        if minimum > maximum {
+            self.set_error(BuildError::InvalidRange { minimum, maximum });
+        } else if report_size < 32 {
+            // Negative ranges need a sign bit
+            let fits = if minimum < 0 {
+                let limit = 1i64 << (report_size.max(1) - 1);
+                minimum as i64 >= -limit && (maximum as i64) < limit
+            } else {
+                (maximum as i64) < 1i64 << report_size
+            };
+            if !fits {
+                self.set_error(BuildError::RangeExceedsReportSize { minimum, maximum, report_size });
+            }
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BuildError, DescriptorBuilder};
+    use crate::hid::descriptor::{CollectionType, DataType, ItemType, MainInputData, MainOutputData, UsagePage};
+
+    #[test]
+    fn build_headset() {
+        let variable = MainInputData { data_type: DataType::Variable, ..Default::default() };
+        let padding = MainInputData { item_type: ItemType::Constant, ..Default::default() };
+        let led = MainOutputData { data_type: DataType::Variable, ..Default::default() };
+
+        let bytes = DescriptorBuilder::new()
+            .usage_page(UsagePage::Telephony)
+            .usage(0x05)
+            .collection(CollectionType::Application, |b| {
+                b.report_id(2)
+                    .logical_range(0, 1)
+                    .report_size(1)
+                    .usage(0x20)
+                    .usage(0x2F)
+                    .report_count(2)
+                    .input(variable)
+                    .report_count(6)
+                    .input(padding)
+                    .push()
+                    .usage_page(UsagePage::Led)
+                    .usage(0x17)
+                    .report_count(1)
+                    .output(led)
+                    .pop()
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            bytes,
+            vec![
+                0x05, 0x0B, 0x09, 0x05, 0xA1, 0x01, 0x85, 0x02, 0x15, 0x00, 0x25, 0x01, 0x75, 0x01, 0x09, 0x20, 0x09,
+                0x2F, 0x95, 0x02, 0x81, 0x02, 0x95, 0x06, 0x81, 0x01, 0xA4, 0x05, 0x08, 0x09, 0x17, 0x95, 0x01, 0x91,
+                0x02, 0xB4, 0xC0,
+            ]
+        );
+    }
+
+    #[test]
+    fn build_errors() {
+        let variable = MainInputData { data_type: DataType::Variable, ..Default::default() };
+
+        let error = DescriptorBuilder::new().logical_range(1, 0).build().unwrap_err();
+        assert_eq!(error, BuildError::InvalidRange { minimum: 1, maximum: 0 });
+
+        let error = DescriptorBuilder::new().report_count(1).input(variable.clone()).build().unwrap_err();
+        assert_eq!(error, BuildError::MissingReportSize);
+
+        let error = DescriptorBuilder::new()
+            .logical_range(-1, 1)
+            .report_size(1)
+            .report_count(1)
+            .input(variable.clone())
+            .build()
+            .unwrap_err();
+        assert_eq!(error, BuildError::RangeExceedsReportSize { minimum: -1, maximum: 1, report_size: 1 });
+
+        let error = DescriptorBuilder::new()
+            .logical_range(0, 255)
+            .report_size(8)
+            .report_count(1)
+            .input(variable)
+            .push()
+            .build()
+            .unwrap_err();
+        assert_eq!(error, BuildError::PushWithoutPop);
+
+        assert_eq!(DescriptorBuilder::new().pop().build().unwrap_err(), BuildError::PopWithoutPush);
+    }
+}
@@ -0,0 +1,141 @@
+use super::descriptor::Report;
+use super::formatter::ReportFormatter;
+use super::layout::{get_report_layouts, ReportKind};
+
+/// Exports a report descriptor as a C array, with the decoded items in comments.
+///
+/// ```text
+/// #define HEADSET_LEN 6
+///
+/// static const uint8_t headset[] = {
+///     0x05, 0x0B,        // Usage Page (Telephony Page)
+///     0xA1, 0x01,        // Collection (Application)
+///     0xC0,              // End Collection
+/// };
+/// ```
+#[derive(Debug, Clone)]
+pub struct CExporter {
+    name: String,
+    size_table: bool,
+}
+
+impl CExporter {
+    /// The name must be a C identifier, the length is defined as its upper case with `_LEN`.
+    pub fn new(name: &str) -> Self {
+        CExporter {
+            name: name.to_string(),
+            size_table: false,
+        }
+    }
+
+    /// Whether a table of the input, output and feature report sizes per Report ID follows the array.
+    ///
+    /// The sizes include the Report ID byte, reports without a Report ID are listed as 0.
+    pub fn size_table(mut self, size_table: bool) -> Self {
+        self.size_table = size_table;
+        self
+    }
+
+    pub fn export(&self, report: &Report) -> String {
+        let length: usize = report.items.iter().map(|item| item.bytes.len()).sum();
+        let items = ReportFormatter::new().summary(false).format(report);
+
+        let mut output = format!("#define {}_LEN {}\n\n", self.name.to_uppercase(), length);
+        output.push_str(&format!("static const uint8_t {}[] = {{\n", self.name));
+        for line in items.lines() {
+            output.push_str(&format!("    {}\n", line));
+        }
+        output.push_str("};\n");
+
+        if self.size_table {
+            output.push_str(&self.export_size_table(report));
+        }
+
+        output
+    }
+
+    fn export_size_table(&self, report: &Report) -> String {
+        let layouts = get_report_layouts(report);
+        let mut report_ids: Vec<Option<u8>> = layouts.iter().map(|layout| layout.report_id).collect();
+        report_ids.dedup();
+
+        let mut output = String::from("\nstatic const struct {\n");
+        output.push_str("    uint8_t report_id;\n");
+        output.push_str("    uint16_t input;\n");
+        output.push_str("    uint16_t output;\n");
+        output.push_str("    uint16_t feature;\n");
+        output.push_str(&format!("}} {}_report_sizes[] = {{\n", self.name));
+
+        for report_id in report_ids {
+            let size = |kind: ReportKind| {
+                layouts
+                    .iter()
+                    .find(|layout| layout.report_id == report_id && layout.kind == kind)
+                    .map(|layout| layout.byte_length())
+                    .unwrap_or(0)
+            };
+            output.push_str(&format!(
+                "    {{ {}, {}, {}, {} }},\n",
+                report_id.unwrap_or(0),
+                size(ReportKind::Input),
+                size(ReportKind::Output),
+                size(ReportKind::Feature)
+            ));
+        }
+        output.push_str("};\n");
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CExporter;
+    use crate::hid::descriptor;
+
+    #[test]
+    fn export_with_size_table() {
+        let bytes = [
+            0x05, 0x0B, // Usage Page (Telephony Page)
+            0x09, 0x05, // Usage (Headset)
+            0xA1, 0x01, // Collection (Application)
+            0x85, 0x02, // Report ID (2)
+            0x75, 0x08, // Report Size (8)
+            0x95, 0x01, // Report Count (1)
+            0x81, 0x01, // Input (Constant, Array, Absolute)
+            0x95, 0x02, // Report Count (2)
+            0x91, 0x01, // Output (Constant, Array, Absolute)
+            0xC0,       // End Collection
+        ];
+        let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+
+        assert_eq!(
+            CExporter::new("headset").size_table(true).export(&report),
+            "\
+#define HEADSET_LEN 19
+
+static const uint8_t headset[] = {
+    0x05, 0x0B,        // Usage Page (Telephony Page)
+    0x09, 0x05,        // Usage (Headset)
+    0xA1, 0x01,        // Collection (Application)
+    0x85, 0x02,        //   Report ID (2)
+    0x75, 0x08,        //   Report Size (8)
+    0x95, 0x01,        //   Report Count (1)
+    0x81, 0x01,        //   Input (Constant, Array, Absolute, No Wrap, Linear, Preferred State, No Null State, Bit Field)
+    0x95, 0x02,        //   Report Count (2)
+    0x91, 0x01,        //   Output (Constant, Array, Absolute, No Wrap, Linear, Preferred State, No Null State, Non-volatile, Bit Field)
+    0xC0,              // End Collection
+};
+
+static const struct {
+    uint8_t report_id;
+    uint16_t input;
+    uint16_t output;
+    uint16_t feature;
+} headset_report_sizes[] = {
+    { 2, 2, 3, 0 },
+};
+"
+        );
+    }
+}
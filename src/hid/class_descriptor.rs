@@ -0,0 +1,250 @@
+use std::fmt;
+
+/// The descriptor type of the HID class descriptor.
+pub const HID_DESCRIPTOR_TYPE: u8 = 0x21;
+/// The descriptor type of the report descriptor.
+pub const REPORT_DESCRIPTOR_TYPE: u8 = 0x22;
+/// The descriptor type of a physical descriptor.
+pub const PHYSICAL_DESCRIPTOR_TYPE: u8 = 0x23;
+
+// bLength, bDescriptorType, bcdHID, bCountryCode and bNumDescriptors
+const HEADER_LENGTH: usize = 6;
+const ENTRY_LENGTH: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassDescriptorError {
+    /// The descriptor type is not 0x21.
+    InvalidType(u8),
+    /// The descriptor ends before the descriptors it lists.
+    Truncated { expected: usize, got: usize },
+}
+
+impl fmt::Display for ClassDescriptorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClassDescriptorError::InvalidType(descriptor_type) => {
+                write!(f, "Descriptor type 0x{:02X} is not a HID class descriptor", descriptor_type)
+            },
+            ClassDescriptorError::Truncated { expected, got } => {
+                write!(f, "The HID class descriptor is {} bytes, but needs {}", got, expected)
+            },
+        }
+    }
+}
+
+impl std::error::Error for ClassDescriptorError {}
+
+/// The HID class descriptor (0x21) that follows a HID interface descriptor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HidClassDescriptor {
+    /// The HID specification release in BCD, as in 0x0111 for 1.11.
+    pub hid_version: u16,
+    pub country_code: CountryCode,
+    pub descriptors: Vec<ClassDescriptorEntry>,
+}
+
+/// A class descriptor listed by the HID class descriptor, usually the report descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClassDescriptorEntry {
+    pub descriptor_type: u8,
+    pub length: u16,
+}
+
+impl HidClassDescriptor {
+    /// The length of the first report descriptor, as requested with GET_DESCRIPTOR.
+    pub fn report_descriptor_length(&self) -> Option<u16> {
+        self.descriptors
+            .iter()
+            .find(|entry| entry.descriptor_type == REPORT_DESCRIPTOR_TYPE)
+            .map(|entry| entry.length)
+    }
+}
+
+/// The country a localized device, usually a keyboard, is made for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CountryCode {
+    NotSupported,
+    Arabic,
+    Belgian,
+    CanadianBilingual,
+    CanadianFrench,
+    CzechRepublic,
+    Danish,
+    Finnish,
+    French,
+    German,
+    Greek,
+    Hebrew,
+    Hungary,
+    International,
+    Italian,
+    JapanKatakana,
+    Korean,
+    LatinAmerican,
+    NetherlandsDutch,
+    Norwegian,
+    PersianFarsi,
+    Poland,
+    Portuguese,
+    Russia,
+    Slovakia,
+    Spanish,
+    Swedish,
+    SwissFrench,
+    SwissGerman,
+    Switzerland,
+    Taiwan,
+    TurkishQ,
+    Uk,
+    Us,
+    Yugoslavia,
+    TurkishF,
+    // 36 - 255
+    Reserved(u8),
+}
+
+const COUNTRY_CODES: [(CountryCode, &str); 36] = [
+    (CountryCode::NotSupported, "Not Supported"),
+    (CountryCode::Arabic, "Arabic"),
+    (CountryCode::Belgian, "Belgian"),
+    (CountryCode::CanadianBilingual, "Canadian-Bilingual"),
+    (CountryCode::CanadianFrench, "Canadian-French"),
+    (CountryCode::CzechRepublic, "Czech Republic"),
+    (CountryCode::Danish, "Danish"),
+    (CountryCode::Finnish, "Finnish"),
+    (CountryCode::French, "French"),
+    (CountryCode::German, "German"),
+    (CountryCode::Greek, "Greek"),
+    (CountryCode::Hebrew, "Hebrew"),
+    (CountryCode::Hungary, "Hungary"),
+    (CountryCode::International, "International (ISO)"),
+    (CountryCode::Italian, "Italian"),
+    (CountryCode::JapanKatakana, "Japan (Katakana)"),
+    (CountryCode::Korean, "Korean"),
+    (CountryCode::LatinAmerican, "Latin American"),
+    (CountryCode::NetherlandsDutch, "Netherlands/Dutch"),
+    (CountryCode::Norwegian, "Norwegian"),
+    (CountryCode::PersianFarsi, "Persian (Farsi)"),
+    (CountryCode::Poland, "Poland"),
+    (CountryCode::Portuguese, "Portuguese"),
+    (CountryCode::Russia, "Russia"),
+    (CountryCode::Slovakia, "Slovakia"),
+    (CountryCode::Spanish, "Spanish"),
+    (CountryCode::Swedish, "Swedish"),
+    (CountryCode::SwissFrench, "Swiss/French"),
+    (CountryCode::SwissGerman, "Swiss/German"),
+    (CountryCode::Switzerland, "Switzerland"),
+    (CountryCode::Taiwan, "Taiwan"),
+    (CountryCode::TurkishQ, "Turkish-Q"),
+    (CountryCode::Uk, "UK"),
+    (CountryCode::Us, "US"),
+    (CountryCode::Yugoslavia, "Yugoslavia"),
+    (CountryCode::TurkishF, "Turkish-F"),
+];
+
+impl CountryCode {
+    pub fn from_value(value: u8) -> Self {
+        match COUNTRY_CODES.get(value as usize) {
+            Some((country_code, _)) => *country_code,
+            None => CountryCode::Reserved(value),
+        }
+    }
+
+    pub fn to_value(self) -> u8 {
+        match self {
+            CountryCode::Reserved(value) => value,
+            _ => COUNTRY_CODES.iter().position(|(country_code, _)| *country_code == self).unwrap() as u8,
+        }
+    }
+}
+
+impl fmt::Display for CountryCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CountryCode::Reserved(value) => write!(f, "Reserved ({})", value),
+            _ => write!(f, "{}", COUNTRY_CODES[self.to_value() as usize].1),
+        }
+    }
+}
+
+/// Parses a HID class descriptor, starting with its bLength byte.
+pub fn parse_hid_class_descriptor(bytes: &[u8]) -> Result<HidClassDescriptor, ClassDescriptorError> {
+    if bytes.len() < HEADER_LENGTH {
+        return Err(ClassDescriptorError::Truncated { expected: HEADER_LENGTH, got: bytes.len() });
+    }
+    if bytes[1] != HID_DESCRIPTOR_TYPE {
+        return Err(ClassDescriptorError::InvalidType(bytes[1]));
+    }
+
+    let descriptor_count = bytes[5] as usize;
+    let expected = HEADER_LENGTH + descriptor_count * ENTRY_LENGTH;
+    if bytes.len() < expected {
+        return Err(ClassDescriptorError::Truncated { expected, got: bytes.len() });
+    }
+
+    let descriptors = bytes[HEADER_LENGTH..expected]
+        .chunks(ENTRY_LENGTH)
+        .map(|entry| ClassDescriptorEntry {
+            descriptor_type: entry[0],
+            length: u16::from_le_bytes([entry[1], entry[2]]),
+        })
+        .collect();
+
+    Ok(HidClassDescriptor {
+        hid_version: u16::from_le_bytes([bytes[2], bytes[3]]),
+        country_code: CountryCode::from_value(bytes[4]),
+        descriptors,
+    })
+}
+
+/// Finds and parses the HID class descriptor among the descriptors that follow an interface
+/// descriptor, as in `InterfaceDescriptor::extra()` of rusb.
+pub fn find_hid_class_descriptor(extra: &[u8]) -> Option<Result<HidClassDescriptor, ClassDescriptorError>> {
+    let mut index = 0;
+    while index + 1 < extra.len() {
+        let length = extra[index] as usize;
+        if length < 2 {
+            return None;
+        }
+        if extra[index + 1] == HID_DESCRIPTOR_TYPE {
+            let end = (index + length).min(extra.len());
+            return Some(parse_hid_class_descriptor(&extra[index..end]));
+        }
+        index += length;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_hid_class_descriptor, parse_hid_class_descriptor, ClassDescriptorError, CountryCode};
+
+    #[test]
+    fn parse_class_descriptor() {
+        // A vendor descriptor before the HID class descriptor of a German keyboard
+        let extra = [0x03, 0x24, 0x00, 0x09, 0x21, 0x11, 0x01, 0x09, 0x01, 0x22, 0x3F, 0x00];
+        let descriptor = find_hid_class_descriptor(&extra).unwrap().unwrap();
+
+        assert_eq!(descriptor.hid_version, 0x0111);
+        assert_eq!(descriptor.country_code, CountryCode::German);
+        assert_eq!(descriptor.country_code.to_string(), "German");
+        assert_eq!(descriptor.report_descriptor_length(), Some(63));
+
+        assert_eq!(CountryCode::from_value(40).to_string(), "Reserved (40)");
+        assert_eq!(CountryCode::from_value(33).to_value(), 33);
+    }
+
+    #[test]
+    fn invalid_class_descriptor() {
+        assert_eq!(
+            parse_hid_class_descriptor(&[0x09, 0x21, 0x11, 0x01, 0x00, 0x02, 0x22, 0x3F, 0x00]),
+            Err(ClassDescriptorError::Truncated { expected: 12, got: 9 })
+        );
+        assert_eq!(parse_hid_class_descriptor(&[0x09, 0x04, 0, 0, 0, 0]), Err(ClassDescriptorError::InvalidType(0x04)));
+        assert_eq!(find_hid_class_descriptor(&[0x07, 0x05, 0x81, 0x03, 0x08, 0x00, 0x0A]), None);
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+
+use super::descriptor::{Report, Usage};
+use super::layout::{get_report_layouts, ReportField, ReportKind, ReportLayout};
+use super::name;
+
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn",
+    "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "static",
+    "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while", "yield",
+];
+
+const HELPERS: &str = "\
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportError {
+    /// The report is shorter than its layout.
+    TooShort { expected: usize, got: usize },
+    /// The Report ID byte doesn't match the report.
+    WrongReportId(u8),
+}
+
+fn get_bits(data: &[u8], bit_offset: u32, bit_count: u32) -> u64 {
+    let mut value = 0u64;
+    for bit in 0..bit_count {
+        let position = (bit_offset + bit) as usize;
+        if data[position / 8] & (1 << (position % 8)) != 0 {
+            value |= 1 << bit;
+        }
+    }
+    value
+}
+
+fn set_bits(data: &mut [u8], bit_offset: u32, bit_count: u32, value: u64) {
+    for bit in 0..bit_count {
+        let position = (bit_offset + bit) as usize;
+        if value & (1 << bit) != 0 {
+            data[position / 8] |= 1 << (position % 8);
+        }
+    }
+}
+";
+
+const SIGN_EXTEND: &str = "
+fn sign_extend(value: u64, bit_count: u32) -> i64 {
+    let shift = 64 - bit_count;
+    ((value << shift) as i64) >> shift
+}
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueType {
+    Bool,
+    Unsigned(u32),
+    Signed(u32),
+}
+
+impl ValueType {
+    fn new(field: &ReportField) -> Self {
+        let bits = match field.report_size {
+            0..=8 => 8,
+            9..=16 => 16,
+            17..=32 => 32,
+            _ => 64,
+        };
+
+        if field.logical_minimum < 0 {
+            ValueType::Signed(bits)
+        } else if field.report_size == 1 && !field.flags.is_array() {
+            ValueType::Bool
+        } else {
+            ValueType::Unsigned(bits)
+        }
+    }
+
+    fn rust_type(&self) -> String {
+        match self {
+            ValueType::Bool => "bool".to_string(),
+            ValueType::Unsigned(bits) => format!("u{}", bits),
+            ValueType::Signed(bits) => format!("i{}", bits),
+        }
+    }
+
+    fn read(&self, bit_offset: &str, report_size: u32) -> String {
+        let bits = format!("get_bits(data, {}, {})", bit_offset, report_size);
+        match self {
+            ValueType::Bool => format!("{} != 0", bits),
+            ValueType::Unsigned(_) => format!("{} as {}", bits, self.rust_type()),
+            ValueType::Signed(_) => format!("sign_extend({}, {}) as {}", bits, report_size, self.rust_type()),
+        }
+    }
+}
+
+// A struct field, holding one value of a Variable field or all values of an Array field
+struct GeneratedField {
+    name: String,
+    doc: String,
+    value_type: ValueType,
+    bit_offset: u32,
+    report_size: u32,
+    array_count: Option<u32>,
+}
+
+/// Generates Rust source with a struct for every report of a descriptor, as laid out by
+/// [`get_report_layouts`].
+///
+/// The structs are named by kind and Report ID, as in `InputReport2`, and have `from_bytes`
+/// and `to_bytes` functions. Every value of a Variable field is a struct field named from its
+/// usage, as in `hook_switch: bool`. Array fields are a struct field holding their raw values.
+/// Constant fields are left out and written as zeros.
+///
+/// The source doesn't depend on this crate, so it can be written from a build script:
+///
+/// ```no_run
+/// use usb_hid_descriptor_parser::hid::{codegen, descriptor};
+///
+/// let bytes = std::fs::read("headset.bin").unwrap();
+/// let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+/// std::fs::write("src/headset_reports.rs", codegen::generate_rust(&report)).unwrap();
+/// ```
+pub fn generate_rust(report: &Report) -> String {
+    let layouts = get_report_layouts(report);
+    let structs: Vec<(&ReportLayout, Vec<GeneratedField>)> =
+        layouts.iter().map(|layout| (layout, get_generated_fields(layout))).collect();
+
+    let mut output = String::from("// Generated by usb_hid_descriptor_parser, do not edit.\n\n");
+    output.push_str(HELPERS);
+    let has_signed = structs
+        .iter()
+        .flat_map(|(_, fields)| fields)
+        .any(|field| matches!(field.value_type, ValueType::Signed(_)));
+    if has_signed {
+        output.push_str(SIGN_EXTEND);
+    }
+
+    for (layout, fields) in &structs {
+        output.push('\n');
+        output.push_str(&generate_struct(layout, fields));
+    }
+
+    output
+}
+
+fn get_struct_name(layout: &ReportLayout) -> String {
+    let kind = match layout.kind {
+        ReportKind::Input => "Input",
+        ReportKind::Output => "Output",
+        ReportKind::Feature => "Feature",
+    };
+
+    match layout.report_id {
+        Some(report_id) => format!("{}Report{}", kind, report_id),
+        None => format!("{}Report", kind),
+    }
+}
+
+fn get_generated_fields(layout: &ReportLayout) -> Vec<GeneratedField> {
+    let mut fields = vec![];
+    let mut name_counts: HashMap<String, u32> = HashMap::new();
+    let mut unique_name = |usage: Usage, suffix: &str| {
+        let name = format!("{}{}", get_field_name(usage), suffix);
+        let count = name_counts.entry(name.clone()).or_insert(0);
+        *count += 1;
+        if *count == 1 {
+            name
+        } else {
+            format!("{}_{}", name, count)
+        }
+    };
+
+    // Fields without usages can't be named, they are padding like Constant fields
+    let data_fields = layout
+        .fields
+        .iter()
+        .filter(|field| !field.flags.is_constant() && !field.usages.is_empty() && field.report_size <= 64);
+
+    for field in data_fields {
+        let value_type = ValueType::new(field);
+
+        if field.flags.is_array() {
+            let first = field.usages[0];
+            let last = field.usages[field.usages.len() - 1];
+            fields.push(GeneratedField {
+                name: unique_name(first, "_array"),
+                doc: format!("Array of {} to {}", get_usage_text(first), get_usage_text(last)),
+                value_type,
+                bit_offset: field.bit_offset,
+                report_size: field.report_size,
+                array_count: Some(field.report_count),
+            });
+            continue;
+        }
+
+        for index in 0..field.report_count {
+            let usage = match field.get_variable_usage(index) {
+                Some(usage) => usage,
+                None => continue,
+            };
+            fields.push(GeneratedField {
+                name: unique_name(usage, ""),
+                doc: get_usage_text(usage),
+                value_type,
+                bit_offset: field.bit_offset + index * field.report_size,
+                report_size: field.report_size,
+                array_count: None,
+            });
+        }
+    }
+
+    fields
+}
+
+fn get_usage_text(usage: Usage) -> String {
+    format!(
+        "{} ({:#06X}:{:#06X})",
+        name::get_usage_name(Some(usage.page), usage.id).trim(),
+        usage.page,
+        usage.id
+    )
+}
+
+// Snake case of the usage name, as in `Phone Key Pound` to `phone_key_pound`
+fn get_field_name(usage: Usage) -> String {
+    let usage_name = name::get_usage_name(Some(usage.page), usage.id).to_lowercase();
+    let mut field_name = String::new();
+    for c in usage_name.chars() {
+        if c.is_ascii_alphanumeric() {
+            field_name.push(c);
+        } else if !field_name.is_empty() && !field_name.ends_with('_') {
+            field_name.push('_');
+        }
+    }
+    let field_name = field_name.trim_end_matches('_');
+
+    if field_name.is_empty() || field_name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("usage_{}", field_name)
+    } else if KEYWORDS.contains(&field_name) {
+        format!("{}_", field_name)
+    } else {
+        field_name.to_string()
+    }
+}
+
+fn generate_struct(layout: &ReportLayout, fields: &[GeneratedField]) -> String {
+    let struct_name = get_struct_name(layout);
+    let mut output = String::new();
+
+    let kind = format!("{:?}", layout.kind).to_lowercase();
+    match layout.report_id {
+        Some(report_id) => output.push_str(&format!("/// The {} report with Report ID {}.\n", kind, report_id)),
+        None => output.push_str(&format!("/// The {} report.\n", kind)),
+    }
+    output.push_str("#[derive(Debug, Clone, PartialEq, Eq)]\n");
+    output.push_str(&format!("pub struct {} {{", struct_name));
+    if !fields.is_empty() {
+        output.push('\n');
+    }
+    for field in fields {
+        let rust_type = match field.array_count {
+            Some(count) => format!("[{}; {}]", field.value_type.rust_type(), count),
+            None => field.value_type.rust_type(),
+        };
+        output.push_str(&format!("    /// {}\n", field.doc));
+        output.push_str(&format!("    pub {}: {},\n", field.name, rust_type));
+    }
+    output.push_str("}\n\n");
+
+    output.push_str(&format!("impl {} {{\n", struct_name));
+    if let Some(report_id) = layout.report_id {
+        output.push_str(&format!("    pub const REPORT_ID: u8 = {};\n", report_id));
+    }
+    output.push_str(&format!("    pub const LENGTH: usize = {};\n\n", layout.byte_length()));
+
+    // from_bytes
+    let data_name = if fields.is_empty() { "_data" } else { "data" };
+    output.push_str("    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReportError> {\n");
+    output.push_str("        if bytes.len() < Self::LENGTH {\n");
+    output.push_str("            return Err(ReportError::TooShort { expected: Self::LENGTH, got: bytes.len() });\n");
+    output.push_str("        }\n");
+    if layout.has_report_id() {
+        output.push_str("        if bytes[0] != Self::REPORT_ID {\n");
+        output.push_str("            return Err(ReportError::WrongReportId(bytes[0]));\n");
+        output.push_str("        }\n");
+        output.push_str(&format!("        let {} = &bytes[1..];\n\n", data_name));
+    } else {
+        output.push_str(&format!("        let {} = bytes;\n\n", data_name));
+    }
+    output.push_str(&format!("        Ok({} {{\n", struct_name));
+    for field in fields {
+        let value = match field.array_count {
+            Some(_) => {
+                let bit_offset = format!("{} + index as u32 * {}", field.bit_offset, field.report_size);
+                format!("core::array::from_fn(|index| {})", field.value_type.read(&bit_offset, field.report_size))
+            },
+            None => field.value_type.read(&field.bit_offset.to_string(), field.report_size),
+        };
+        output.push_str(&format!("            {}: {},\n", field.name, value));
+    }
+    output.push_str("        })\n");
+    output.push_str("    }\n\n");
+
+    // to_bytes
+    output.push_str("    pub fn to_bytes(&self) -> Vec<u8> {\n");
+    let bytes_mut = if layout.has_report_id() || !fields.is_empty() { "mut " } else { "" };
+    output.push_str(&format!("        let {}bytes = vec![0; Self::LENGTH];\n", bytes_mut));
+    if layout.has_report_id() {
+        output.push_str("        bytes[0] = Self::REPORT_ID;\n");
+    }
+    if !fields.is_empty() {
+        let data = if layout.has_report_id() { "&mut bytes[1..]" } else { "&mut bytes[..]" };
+        output.push_str(&format!("        let data = {};\n", data));
+    }
+    for field in fields {
+        match field.array_count {
+            Some(_) => {
+                output.push_str(&format!("        for (index, value) in self.{}.iter().enumerate() {{\n", field.name));
+                output.push_str(&format!(
+                    "            set_bits(data, {} + index as u32 * {}, {}, *value as u64);\n",
+                    field.bit_offset, field.report_size, field.report_size
+                ));
+                output.push_str("        }\n");
+            },
+            None => output.push_str(&format!(
+                "        set_bits(data, {}, {}, self.{} as u64);\n",
+                field.bit_offset, field.report_size, field.name
+            )),
+        }
+    }
+    output.push_str("        bytes\n");
+    output.push_str("    }\n");
+    output.push_str("}\n");
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::generate_rust;
+    use crate::hid::descriptor;
+
+    #[test]
+    fn generate_headset() {
+        let bytes = [
+            0x05, 0x0B,       // Usage Page (Telephony Page)
+            0x09, 0x05,       // Usage (Headset)
+            0xA1, 0x01,       // Collection (Application)
+            0x85, 0x02,       // Report ID (2)
+            0x15, 0x00,       // Logical Minimum (0)
+            0x25, 0x01,       // Logical Maximum (1)
+            0x09, 0x20,       // Usage (Hook Switch)
+            0x09, 0x2F,       // Usage (Phone Mute)
+            0x75, 0x01,       // Report Size (1)
+            0x95, 0x02,       // Report Count (2)
+            0x81, 0x02,       // Input (Data, Variable, Absolute)
+            0x95, 0x06,       // Report Count (6)
+            0x81, 0x01,       // Input (Constant, Array, Absolute)
+            0x05, 0x0C,       // Usage Page (Consumer Page)
+            0x09, 0xE0,       // Usage (Volume)
+            0x16, 0x00, 0xFF, // Logical Minimum (-256)
+            0x26, 0xFF, 0x00, // Logical Maximum (255)
+            0x75, 0x10,       // Report Size (16)
+            0x95, 0x01,       // Report Count (1)
+            0x91, 0x06,       // Output (Data, Variable, Relative)
+            0xC0,             // End Collection
+        ];
+        let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+        let source = generate_rust(&report);
+
+        assert!(source.contains("pub struct InputReport2 {"));
+        assert!(source.contains("    pub hook_switch: bool,\n"));
+        assert!(source.contains("            phone_mute: get_bits(data, 1, 1) != 0,\n"));
+        assert!(source.contains("pub struct OutputReport2 {"));
+        assert!(source.contains("    pub volume: i16,\n"));
+        assert!(source.contains("            volume: sign_extend(get_bits(data, 0, 16), 16) as i16,\n"));
+        assert!(source.contains("        set_bits(data, 0, 16, self.volume as u64);\n"));
+        assert!(source.contains("fn sign_extend("));
+    }
+}
@@ -0,0 +1,127 @@
+use super::descriptor::{CollectionType, HidItem, HidLocalTag, HidMainTag, ParseError, Report, Usage};
+
+/// A collection in a report descriptor, with the collections and main items nested inside it.
+#[derive(Debug)]
+pub struct CollectionNode<'a> {
+    pub collection_type: CollectionType,
+    // The first Usage preceding the Collection item, if any
+    pub usage: Option<Usage>,
+    pub item: &'a HidItem,
+    pub children: Vec<CollectionNode<'a>>,
+    // The Input, Output and Feature items directly inside this collection
+    pub items: Vec<&'a HidItem>,
+}
+
+/// Builds the collection tree of a report, returning the top level collections.
+///
+/// Main items outside of any collection are not part of the tree.
+pub fn get_collections(report: &Report) -> Result<Vec<CollectionNode<'_>>, ParseError> {
+    let mut collections: Vec<CollectionNode> = vec![];
+    // Collections that are still open, with the offset of their Collection item
+    let mut open: Vec<(CollectionNode, usize)> = vec![];
+    let mut usage: Option<Usage> = None;
+
+    let mut offset = 0;
+    for item in &report.items {
+        match &item.local_tag {
+            Some(HidLocalTag::Usage(_)) if usage.is_none() => usage = item.usage,
+            _ => {},
+        }
+
+        match &item.main_tag {
+            Some(HidMainTag::Collection(collection_type)) => {
+                let node = CollectionNode {
+                    collection_type: *collection_type,
+                    usage,
+                    item,
+                    children: vec![],
+                    items: vec![],
+                };
+                open.push((node, offset));
+            },
+            Some(HidMainTag::EndCollection) => {
+                let node = match open.pop() {
+                    Some((node, _)) => node,
+                    None => return Err(ParseError::UnbalancedEndCollection { offset, prefix: item.bytes[0] }),
+                };
+                match open.last_mut() {
+                    Some((parent, _)) => parent.children.push(node),
+                    None => collections.push(node),
+                }
+            },
+            Some(HidMainTag::Input(_)) | Some(HidMainTag::Output(_)) | Some(HidMainTag::Feature(_)) => {
+                if let Some((parent, _)) = open.last_mut() {
+                    parent.items.push(item);
+                }
+            },
+            Some(HidMainTag::Reserved) | None => {},
+        }
+
+        // Local items only apply to the next main item
+        if item.main_tag.is_some() {
+            usage = None;
+        }
+        offset += item.bytes.len();
+    }
+
+    if let Some((node, offset)) = open.pop() {
+        return Err(ParseError::UnclosedCollection { offset, prefix: node.item.bytes[0] });
+    }
+
+    Ok(collections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get_collections;
+    use crate::hid::descriptor;
+
+    #[test]
+    fn nested_collections() {
+        let bytes = [
+            0x05, 0x0B, // Usage Page (Telephony Page)
+            0x09, 0x05, // Usage (Headset)
+            0xA1, 0x01, // Collection (Application)
+            0x75, 0x01, // Report Size (1)
+            0x95, 0x01, // Report Count (1)
+            0x09, 0x20, // Usage (Hook Switch)
+            0x81, 0x02, // Input (Data, Variable, Absolute)
+            0x09, 0x06, // Usage (Telephony Key Pad)
+            0xA1, 0x02, // Collection (Logical)
+            0x19, 0xB0, // Usage Minimum (Phone Key 0)
+            0x29, 0xBB, // Usage Maximum (Phone Key Pound)
+            0x81, 0x00, // Input (Data, Array, Absolute)
+            0xC0,       // End Collection
+            0x91, 0x02, // Output (Data, Variable, Absolute)
+            0xC0,       // End Collection
+        ];
+        let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+        let collections = get_collections(&report).unwrap();
+
+        assert_eq!(collections.len(), 1);
+        let application = &collections[0];
+        assert_eq!(application.collection_type, descriptor::CollectionType::Application);
+        assert_eq!(application.usage, Some(descriptor::Usage::new(0x0B, 0x05)));
+        assert_eq!(application.items.len(), 2);
+        assert!(std::ptr::eq(application.items[0], &report.items[6]));
+        assert!(std::ptr::eq(application.items[1], &report.items[13]));
+
+        assert_eq!(application.children.len(), 1);
+        let key_pad = &application.children[0];
+        assert_eq!(key_pad.collection_type, descriptor::CollectionType::Logical);
+        assert_eq!(key_pad.usage, Some(descriptor::Usage::new(0x0B, 0x06)));
+        assert_eq!(key_pad.items.len(), 1);
+        assert!(key_pad.children.is_empty());
+    }
+
+    #[test]
+    fn unbalanced_collections() {
+        let report = descriptor::try_get_descriptor_report(&[0xA1, 0x01, 0xC0, 0xC0]).unwrap();
+        let error = get_collections(&report).unwrap_err();
+        assert_eq!(error, descriptor::ParseError::UnbalancedEndCollection { offset: 3, prefix: 0xC0 });
+
+        let report = descriptor::try_get_descriptor_report(&[0xA1, 0x01, 0xA1, 0x02, 0xC0]).unwrap();
+        let error = get_collections(&report).unwrap_err();
+        assert_eq!(error, descriptor::ParseError::UnclosedCollection { offset: 0, prefix: 0xA1 });
+    }
+}
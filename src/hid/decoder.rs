@@ -0,0 +1,204 @@
+use std::fmt;
+
+use super::descriptor::Usage;
+use super::layout::{find_report_layout, get_bits, ReportField, ReportKind, ReportLayout};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The report is empty, so it has no Report ID byte.
+    Empty,
+    /// No layout matches the Report ID byte of the report.
+    UnknownReportId(u8),
+    /// The report is shorter than its layout.
+    TooShort { expected: usize, got: usize },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Empty => write!(f, "The report is empty"),
+            DecodeError::UnknownReportId(report_id) => write!(f, "No report with Report ID {}", report_id),
+            DecodeError::TooShort { expected, got } => {
+                write!(f, "The report is {} bytes, but its layout needs {} bytes", got, expected)
+            },
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldValue {
+    /// The logical value of every usage of a Variable field.
+    Variable(Vec<(Usage, i64)>),
+    /// The usages that are active in an Array field.
+    Array(Vec<Usage>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedField<'a> {
+    pub field: &'a ReportField,
+    pub value: FieldValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedReport<'a> {
+    pub layout: &'a ReportLayout,
+    // Every field with usages, padding fields are left out
+    pub fields: Vec<DecodedField<'a>>,
+}
+
+impl DecodedReport<'_> {
+    /// The logical value of a usage in one of the Variable fields of the report.
+    pub fn get_value(&self, usage: Usage) -> Option<i64> {
+        self.fields.iter().find_map(|field| match &field.value {
+            FieldValue::Variable(values) => values.iter().find(|(u, _)| *u == usage).map(|(_, value)| *value),
+            FieldValue::Array(_) => None,
+        })
+    }
+
+    /// Whether a usage is active in one of the Array fields of the report.
+    pub fn is_active(&self, usage: Usage) -> bool {
+        self.fields.iter().any(|field| match &field.value {
+            FieldValue::Variable(_) => false,
+            FieldValue::Array(usages) => usages.contains(&usage),
+        })
+    }
+}
+
+/// Decodes an input report as read from the interrupt IN endpoint.
+///
+/// The layout is picked by the Report ID byte when the descriptor uses Report IDs.
+pub fn decode_input_report<'a>(layouts: &'a [ReportLayout], data: &[u8]) -> Result<DecodedReport<'a>, DecodeError> {
+    let uses_report_ids = layouts
+        .iter()
+        .any(|layout| layout.kind == ReportKind::Input && layout.has_report_id());
+    let report_id = if uses_report_ids {
+        match data.first() {
+            Some(report_id) => Some(*report_id),
+            None => return Err(DecodeError::Empty),
+        }
+    } else {
+        None
+    };
+
+    match find_report_layout(layouts, report_id, ReportKind::Input) {
+        Some(layout) => decode_report(layout, data),
+        None => Err(DecodeError::UnknownReportId(report_id.unwrap_or(0))),
+    }
+}
+
+/// Decodes a report of any kind with a known layout, including its Report ID byte if it has one.
+pub fn decode_report<'a>(layout: &'a ReportLayout, data: &[u8]) -> Result<DecodedReport<'a>, DecodeError> {
+    if data.len() < layout.byte_length() {
+        return Err(DecodeError::TooShort { expected: layout.byte_length(), got: data.len() });
+    }
+    let data = match layout.report_id {
+        Some(report_id) if data[0] != report_id => return Err(DecodeError::UnknownReportId(data[0])),
+        Some(_) => &data[1..],
+        None => data,
+    };
+
+    let fields = layout
+        .fields
+        .iter()
+        .filter(|field| !field.usages.is_empty())
+        .map(|field| DecodedField {
+            field,
+            value: decode_field(field, data),
+        })
+        .collect();
+
+    Ok(DecodedReport { layout, fields })
+}
+
+fn decode_field(field: &ReportField, data: &[u8]) -> FieldValue {
+    let values = (0..field.report_count).map(|index| {
+        let raw = get_bits(data, field.bit_offset + index * field.report_size, field.report_size);
+        (index, get_logical_value(field, raw))
+    });
+
+    if field.flags.is_array() {
+        FieldValue::Array(values.filter_map(|(_, value)| field.get_array_usage(value)).collect())
+    } else {
+        FieldValue::Variable(
+            values
+                .filter_map(|(index, value)| field.get_variable_usage(index).map(|usage| (usage, value)))
+                .collect(),
+        )
+    }
+}
+
+fn get_logical_value(field: &ReportField, raw: u64) -> i64 {
+    // Values are signed when the Logical Minimum is negative
+    let size = field.report_size.min(64);
+    if field.logical_minimum < 0 && size > 0 && size < 64 {
+        let shift = 64 - size;
+        ((raw << shift) as i64) >> shift
+    } else {
+        raw as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_input_report, DecodeError, FieldValue};
+    use crate::hid::descriptor::{self, Usage};
+    use crate::hid::layout::get_report_layouts;
+
+    #[test]
+    fn decode_with_report_id() {
+        let bytes = [
+            0x05, 0x0B,       // Usage Page (Telephony Page)
+            0x09, 0x05,       // Usage (Headset)
+            0xA1, 0x01,       // Collection (Application)
+            0x85, 0x02,       // Report ID (2)
+            0x15, 0x00,       // Logical Minimum (0)
+            0x25, 0x01,       // Logical Maximum (1)
+            0x09, 0x20,       // Usage (Hook Switch)
+            0x09, 0x2F,       // Usage (Phone Mute)
+            0x75, 0x01,       // Report Size (1)
+            0x95, 0x02,       // Report Count (2)
+            0x81, 0x02,       // Input (Data, Variable, Absolute)
+            0x95, 0x06,       // Report Count (6)
+            0x81, 0x01,       // Input (Constant, Array, Absolute)
+            0x19, 0xB0,       // Usage Minimum (Phone Key 0)
+            0x29, 0xBB,       // Usage Maximum (Phone Key Pound)
+            0x15, 0x01,       // Logical Minimum (1)
+            0x25, 0x0C,       // Logical Maximum (12)
+            0x75, 0x04,       // Report Size (4)
+            0x95, 0x02,       // Report Count (2)
+            0x81, 0x40,       // Input (Data, Array, Absolute, Null State)
+            0x85, 0x03,       // Report ID (3)
+            0x05, 0x0C,       // Usage Page (Consumer Page)
+            0x09, 0xE0,       // Usage (Volume)
+            0x16, 0x00, 0xFF, // Logical Minimum (-256)
+            0x26, 0xFF, 0x00, // Logical Maximum (255)
+            0x75, 0x10,       // Report Size (16)
+            0x95, 0x01,       // Report Count (1)
+            0x81, 0x06,       // Input (Data, Variable, Relative)
+            0xC0,             // End Collection
+        ];
+        let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+        let layouts = get_report_layouts(&report);
+
+        // Hook Switch on, key 3 pressed, no second key
+        let decoded = decode_input_report(&layouts, &[0x02, 0b0000_0001, 0x04]).unwrap();
+        assert_eq!(decoded.layout.report_id, Some(2));
+        assert_eq!(decoded.fields.len(), 2);
+        assert_eq!(
+            decoded.fields[0].value,
+            FieldValue::Variable(vec![(Usage::new(0x0B, 0x20), 1), (Usage::new(0x0B, 0x2F), 0)])
+        );
+        assert_eq!(decoded.fields[1].value, FieldValue::Array(vec![Usage::new(0x0B, 0xB3)]));
+        assert_eq!(decoded.get_value(Usage::new(0x0B, 0x20)), Some(1));
+        assert!(decoded.is_active(Usage::new(0x0B, 0xB3)));
+
+        let decoded = decode_input_report(&layouts, &[0x03, 0xFE, 0xFF]).unwrap();
+        assert_eq!(decoded.get_value(Usage::new(0x0C, 0xE0)), Some(-2));
+
+        assert_eq!(decode_input_report(&layouts, &[0x04, 0x00]).unwrap_err(), DecodeError::UnknownReportId(4));
+        assert_eq!(decode_input_report(&layouts, &[0x02, 0x00]).unwrap_err(), DecodeError::TooShort { expected: 3, got: 2 });
+        assert_eq!(decode_input_report(&layouts, &[]).unwrap_err(), DecodeError::Empty);
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt;
+
+use super::descriptor::Usage;
+use super::layout::{set_bits, ReportField, ReportLayout};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    /// None of the Data fields of the report has the usage.
+    UnknownUsage(Usage),
+    /// The value is outside of the Logical Minimum and Maximum of its field.
+    OutOfRange {
+        usage: Usage,
+        value: i64,
+        minimum: i64,
+        maximum: i64,
+    },
+    /// More usages are active than the Array field has room for.
+    ArrayFull(Usage),
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::UnknownUsage(usage) => {
+                write!(f, "Usage {:#06X}:{:#06X} is not in the report", usage.page, usage.id)
+            },
+            EncodeError::OutOfRange { usage, value, minimum, maximum } => write!(
+                f,
+                "Value {} of usage {:#06X}:{:#06X} is outside of {} to {}",
+                value, usage.page, usage.id, minimum, maximum
+            ),
+            EncodeError::ArrayFull(usage) => {
+                write!(f, "No room left for usage {:#06X}:{:#06X} in its Array field", usage.page, usage.id)
+            },
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+/// Encodes an output or feature report from usage values, including its Report ID byte if it has one.
+///
+/// Usages of Variable fields are set to their logical value. Usages of Array fields are active
+/// when set to 1 and inactive when set to 0. Constant fields and unset values are left zeroed.
+pub fn encode_report(layout: &ReportLayout, values: &[(Usage, i64)]) -> Result<Vec<u8>, EncodeError> {
+    let mut report = vec![0u8; layout.byte_length()];
+    let data = match layout.report_id {
+        Some(report_id) => {
+            report[0] = report_id;
+            &mut report[1..]
+        },
+        None => &mut report[..],
+    };
+
+    // Next free slot of every Array field
+    let mut array_slots = vec![0u32; layout.fields.len()];
+
+    for (usage, value) in values {
+        let (field_index, field, usage_index) = match find_usage(layout, *usage) {
+            Some(found) => found,
+            None => return Err(EncodeError::UnknownUsage(*usage)),
+        };
+
+        if field.flags.is_array() {
+            if !(0..=1).contains(value) {
+                return Err(EncodeError::OutOfRange { usage: *usage, value: *value, minimum: 0, maximum: 1 });
+            }
+            if *value == 0 {
+                continue;
+            }
+
+            let slot = array_slots[field_index];
+            if slot >= field.report_count {
+                return Err(EncodeError::ArrayFull(*usage));
+            }
+            array_slots[field_index] += 1;
+
+            let array_value = field.logical_minimum + usage_index as i64;
+            set_bits(data, field.bit_offset + slot * field.report_size, field.report_size, array_value as u64);
+        } else {
+            if *value < field.logical_minimum || *value > field.logical_maximum {
+                return Err(EncodeError::OutOfRange {
+                    usage: *usage,
+                    value: *value,
+                    minimum: field.logical_minimum,
+                    maximum: field.logical_maximum,
+                });
+            }
+
+            let bit_offset = field.bit_offset + usage_index * field.report_size;
+            set_bits(data, bit_offset, field.report_size, *value as u64);
+        }
+    }
+
+    Ok(report)
+}
+
+fn find_usage(layout: &ReportLayout, usage: Usage) -> Option<(usize, &ReportField, u32)> {
+    layout
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| !field.flags.is_constant())
+        .find_map(|(field_index, field)| {
+            let usage_index = field.usages.iter().position(|u| *u == usage)? as u32;
+            if !field.flags.is_array() && usage_index >= field.report_count {
+                return None;
+            }
+            Some((field_index, field, usage_index))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_report, EncodeError};
+    use crate::hid::decoder::decode_report;
+    use crate::hid::descriptor::{self, Usage};
+    use crate::hid::layout::{find_report_layout, get_report_layouts, ReportKind};
+
+    const LEDS: [u8; 47] = [
+        0x05, 0x0B,       // Usage Page (Telephony Page)
+        0x09, 0x05,       // Usage (Headset)
+        0xA1, 0x01,       // Collection (Application)
+        0x85, 0x02,       // Report ID (2)
+        0x05, 0x08,       // Usage Page (LED Page)
+        0x15, 0x00,       // Logical Minimum (0)
+        0x25, 0x01,       // Logical Maximum (1)
+        0x09, 0x17,       // Usage (Off-Hook)
+        0x09, 0x09,       // Usage (Mute)
+        0x09, 0x18,       // Usage (Ring)
+        0x75, 0x01,       // Report Size (1)
+        0x95, 0x03,       // Report Count (3)
+        0x91, 0x22,       // Output (Data, Variable, Absolute, No Preferred State)
+        0x95, 0x05,       // Report Count (5)
+        0x91, 0x01,       // Output (Constant, Array, Absolute)
+        0x85, 0x05,       // Report ID (5)
+        0x06, 0x00, 0xFF, // Usage Page (Vendor-defined 0xFF00)
+        0x09, 0x01,       // Usage (0x01)
+        0x26, 0xFF, 0x00, // Logical Maximum (255)
+        0x75, 0x08,       // Report Size (8)
+        0x95, 0x01,       // Report Count (1)
+        0xB1, 0x02,       // Feature (Data, Variable, Absolute)
+        0xC0,             // End Collection
+    ];
+
+    #[test]
+    fn encode_leds() {
+        let report = descriptor::try_get_descriptor_report(&LEDS).unwrap();
+        let layouts = get_report_layouts(&report);
+        let output = find_report_layout(&layouts, Some(2), ReportKind::Output).unwrap();
+
+        let data = encode_report(output, &[(Usage::new(0x08, 0x17), 1), (Usage::new(0x08, 0x18), 1)]).unwrap();
+        assert_eq!(data, vec![0x02, 0b0000_0101]);
+
+        let decoded = decode_report(output, &data).unwrap();
+        assert_eq!(decoded.get_value(Usage::new(0x08, 0x09)), Some(0));
+        assert_eq!(decoded.get_value(Usage::new(0x08, 0x18)), Some(1));
+
+        let feature = find_report_layout(&layouts, Some(5), ReportKind::Feature).unwrap();
+        let data = encode_report(feature, &[(Usage::new(0xFF00, 0x01), 200)]).unwrap();
+        assert_eq!(data, vec![0x05, 200]);
+    }
+
+    #[test]
+    fn encode_errors() {
+        let report = descriptor::try_get_descriptor_report(&LEDS).unwrap();
+        let layouts = get_report_layouts(&report);
+        let output = find_report_layout(&layouts, Some(2), ReportKind::Output).unwrap();
+
+        assert_eq!(
+            encode_report(output, &[(Usage::new(0x08, 0x17), 2)]).unwrap_err(),
+            EncodeError::OutOfRange { usage: Usage::new(0x08, 0x17), value: 2, minimum: 0, maximum: 1 }
+        );
+        assert_eq!(
+            encode_report(output, &[(Usage::new(0x0B, 0x20), 1)]).unwrap_err(),
+            EncodeError::UnknownUsage(Usage::new(0x0B, 0x20))
+        );
+    }
+}
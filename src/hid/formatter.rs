@@ -0,0 +1,290 @@
+use super::descriptor::{HidGlobalTag, HidItem, HidLocalTag, HidMainTag, Report};
+use super::name;
+
+/// The usage page names of the eleccelerator parser, where they differ from the HID Usage Tables.
+pub(crate) const ELECCELERATOR_USAGE_PAGES: [(u32, &str); 14] = [
+    (0x01, "Generic Desktop Ctrls"),
+    (0x02, "Sim Ctrls"),
+    (0x03, "VR Ctrls"),
+    (0x04, "Sport Ctrls"),
+    (0x05, "Game Ctrls"),
+    (0x06, "Generic Dev Ctrls"),
+    (0x07, "Kbrd/Keypad"),
+    (0x08, "LEDs"),
+    (0x0D, "Digitizer"),
+    (0x0F, "PID Page"),
+    (0x14, "Alphanumeric Display"),
+    (0x40, "Medical Instruments"),
+    (0x8C, "Bar Code Scanner Page"),
+    (0x8E, "Magnetic Stripe Reading (MSR) Devices"),
+];
+
+// The cleared and set names of the main item flags, by bit, as eleccelerator abbreviates them
+const ELECCELERATOR_FLAGS: [(&str, &str); 8] = [
+    ("Data", "Const"),
+    ("Array", "Var"),
+    ("Abs", "Rel"),
+    ("No Wrap", "Wrap"),
+    ("Linear", "Non-linear"),
+    ("Preferred State", "No Preferred State"),
+    ("No Null Position", "Null State"),
+    ("Non-volatile", "Volatile"),
+];
+
+/// Formats a report descriptor as annotated hex bytes, one item per line.
+///
+/// ```text
+/// 0x05, 0x0B,        // Usage Page (Telephony Page)
+/// 0xA1, 0x01,        // Collection (Application)
+/// 0x09, 0x20,        //   Usage (Hook Switch)
+/// 0xC0,              // End Collection
+///
+/// // 8 bytes, 4 items
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReportFormatter {
+    comment_column: usize,
+    minimum_gap: usize,
+    indent: String,
+    item_types: bool,
+    summary: bool,
+    item_count: bool,
+    eleccelerator_names: bool,
+}
+
+impl Default for ReportFormatter {
+    fn default() -> Self {
+        ReportFormatter {
+            comment_column: 19,
+            minimum_gap: 2,
+            indent: "  ".to_string(),
+            item_types: false,
+            summary: true,
+            item_count: true,
+            eleccelerator_names: false,
+        }
+    }
+}
+
+impl ReportFormatter {
+    pub fn new() -> Self {
+        ReportFormatter::default()
+    }
+
+    /// The output of the eleccelerator USB Descriptor and Request Parser.
+    pub fn eleccelerator() -> Self {
+        ReportFormatter::new().item_count(false).eleccelerator_names(true)
+    }
+
+    /// The column the comments start at, when the bytes leave room for it.
+    pub fn comment_column(mut self, column: usize) -> Self {
+        self.comment_column = column;
+        self
+    }
+
+    /// The spaces kept between the bytes and a comment that is pushed past its column.
+    pub fn minimum_gap(mut self, gap: usize) -> Self {
+        self.minimum_gap = gap;
+        self
+    }
+
+    /// The indentation of a comment for every collection it's in.
+    pub fn indent(mut self, indent: &str) -> Self {
+        self.indent = indent.to_string();
+        self
+    }
+
+    /// Whether comments end with the item type, as in `[Global]`.
+    pub fn item_types(mut self, item_types: bool) -> Self {
+        self.item_types = item_types;
+        self
+    }
+
+    /// Whether a summary line with the total length follows the items.
+    pub fn summary(mut self, summary: bool) -> Self {
+        self.summary = summary;
+        self
+    }
+
+    /// Whether the summary line counts the items too.
+    pub fn item_count(mut self, item_count: bool) -> Self {
+        self.item_count = item_count;
+        self
+    }
+
+    /// Whether items are named as the eleccelerator parser names them, as in `Usage Page (Telephony)`,
+    /// `Push` and `Input (Data,Var,Abs,No Wrap,Linear,Preferred State,No Null Position)`.
+    pub fn eleccelerator_names(mut self, eleccelerator_names: bool) -> Self {
+        self.eleccelerator_names = eleccelerator_names;
+        self
+    }
+
+    pub fn format(&self, report: &Report) -> String {
+        let mut output = String::new();
+        let mut depth: usize = 0;
+
+        for item in &report.items {
+            // End Collection lines up with its Collection
+            let main_tag = item.main_tag.as_ref();
+            if matches!(main_tag, Some(HidMainTag::EndCollection)) {
+                depth = depth.saturating_sub(1);
+            }
+
+            let bytes: String = item.bytes.iter().map(|byte| format!("0x{:02X}, ", byte)).collect();
+            let bytes = bytes.trim_end();
+            let padding = self.comment_column.saturating_sub(bytes.len()).max(self.minimum_gap);
+            let mut text = if self.eleccelerator_names { get_eleccelerator_text(item) } else { item.text() };
+            if self.item_types {
+                text = format!("{} [{}]", text, item.item_type_name());
+            }
+
+            output.push_str(&format!("{}{}// {}{}\n", bytes, " ".repeat(padding), self.indent.repeat(depth), text));
+
+            if matches!(main_tag, Some(HidMainTag::Collection(_))) {
+                depth += 1;
+            }
+        }
+
+        if self.summary {
+            let length: usize = report.items.iter().map(|item| item.bytes.len()).sum();
+            if self.item_count {
+                output.push_str(&format!("\n// {} bytes, {} items\n", length, report.items.len()));
+            } else {
+                output.push_str(&format!("\n// {} bytes\n", length));
+            }
+        }
+
+        output
+    }
+}
+
+fn get_eleccelerator_text(item: &HidItem) -> String {
+    match (&item.main_tag, &item.global_tag, &item.local_tag) {
+        (Some(HidMainTag::Input(data)), _, _) => format!("Input ({})", get_eleccelerator_flags(data.to_bits(), false)),
+        (Some(HidMainTag::Output(data)), _, _) => format!("Output ({})", get_eleccelerator_flags(data.to_bits(), true)),
+        (Some(HidMainTag::Feature(data)), _, _) => format!("Feature ({})", get_eleccelerator_flags(data.to_bits(), true)),
+        (_, Some(HidGlobalTag::UsagePage(usage_page)), _) => {
+            format!("Usage Page ({})", get_eleccelerator_usage_page_name(*usage_page))
+        },
+        (_, Some(HidGlobalTag::Push(_)), _) => "Push".to_string(),
+        (_, Some(HidGlobalTag::Pop(_)), _) => "Pop".to_string(),
+        (_, _, Some(HidLocalTag::Usage(_))) => format!("Usage ({})", get_eleccelerator_usage_name(item)),
+        (_, _, Some(HidLocalTag::UsageMinimum(_))) => format!("Usage Minimum ({})", get_eleccelerator_usage_name(item)),
+        (_, _, Some(HidLocalTag::UsageMaximum(_))) => format!("Usage Maximum ({})", get_eleccelerator_usage_name(item)),
+        _ => item.text(),
+    }
+}
+
+fn get_eleccelerator_flags(bits: u32, has_volatility: bool) -> String {
+    let count = if has_volatility { 8 } else { 7 };
+    let mut flags: Vec<&str> = ELECCELERATOR_FLAGS[..count]
+        .iter()
+        .enumerate()
+        .map(|(bit, (cleared, set))| if bits & (1 << bit) == 0 { *cleared } else { *set })
+        .collect();
+    if bits & (1 << 8) != 0 {
+        flags.push("Buffered Bytes");
+    }
+
+    flags.join(",")
+}
+
+fn get_eleccelerator_usage_page_name(usage_page: u32) -> String {
+    if let Some((_, page_name)) = ELECCELERATOR_USAGE_PAGES.iter().find(|(page, _)| *page == usage_page) {
+        return page_name.to_string();
+    }
+
+    match usage_page {
+        0xFF00..=0xFFFF => format!("Vendor Defined 0x{:04X}", usage_page),
+        _ => {
+            let page_name = name::get_usage_page_name(usage_page);
+            page_name.strip_suffix(" Page").unwrap_or(&page_name).to_string()
+        },
+    }
+}
+
+// Eleccelerator names usages on the pages it knows, and prints the others in hex
+fn get_eleccelerator_usage_name(item: &HidItem) -> String {
+    if item.is_extended_usage() {
+        return format!("0x{:06X}", item.unsigned_data());
+    }
+
+    let hex = format!("0x{:0width$X}", item.unsigned_data(), width = item.data().len().max(1) * 2);
+
+    match item.usage_page {
+        Some(0x07 | 0x09 | 0x0A) | Some(0xFF00..=0xFFFF) | None => hex,
+        Some(usage_page) => {
+            let usage_name = name::get_usage_name(Some(usage_page), item.unsigned_data());
+            if usage_name == "Reserved" || usage_name.starts_with("0x") {
+                hex
+            } else {
+                usage_name
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReportFormatter;
+    use crate::hid::assembler::assemble;
+    use crate::hid::descriptor;
+
+    const HEADSET: [u8; 19] = [
+        0x05, 0x0B, 0x09, 0x05, 0xA1, 0x01, 0x15, 0x00, 0x26, 0xFF, 0x00, 0x0B, 0xE9, 0x00, 0x0C, 0x00, 0xA4, 0xB4,
+        0xC0,
+    ];
+
+    // As printed by the eleccelerator parser
+    const ELECCELERATOR_DUMP: &str = "\
+0x05, 0x0B,        // Usage Page (Telephony)
+0x09, 0x05,        // Usage (Headset)
+0xA1, 0x01,        // Collection (Application)
+0x85, 0x02,        //   Report ID (2)
+0x15, 0x00,        //   Logical Minimum (0)
+0x25, 0x01,        //   Logical Maximum (1)
+0x75, 0x01,        //   Report Size (1)
+0x09, 0x20,        //   Usage (Hook Switch)
+0x95, 0x01,        //   Report Count (1)
+0x81, 0x02,        //   Input (Data,Var,Abs,No Wrap,Linear,Preferred State,No Null Position)
+0x95, 0x07,        //   Report Count (7)
+0x81, 0x01,        //   Input (Const,Array,Abs,No Wrap,Linear,Preferred State,No Null Position)
+0xA4,              //   Push
+0x05, 0x08,        //   Usage Page (LEDs)
+0x09, 0x17,        //   Usage (Off-Hook)
+0x95, 0x01,        //   Report Count (1)
+0x91, 0x22,        //   Output (Data,Var,Abs,No Wrap,Linear,No Preferred State,No Null Position,Non-volatile)
+0xB4,              //   Pop
+0x06, 0x00, 0xFF,  //   Usage Page (Vendor Defined 0xFF00)
+0x09, 0x01,        //   Usage (0x01)
+0x0B, 0xE9, 0x00, 0x0C, 0x00,  //   Usage (0x0C00E9)
+0xC0,              // End Collection
+
+// 45 bytes
+";
+
+    #[test]
+    fn format_eleccelerator() {
+        let bytes = assemble(ELECCELERATOR_DUMP).unwrap();
+        let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+
+        assert_eq!(ReportFormatter::eleccelerator().format(&report), ELECCELERATOR_DUMP);
+    }
+
+    #[test]
+    fn format_options() {
+        let report = descriptor::try_get_descriptor_report(&HEADSET[..6]).unwrap();
+
+        assert_eq!(
+            ReportFormatter::new().comment_column(0).minimum_gap(1).item_types(true).format(&report),
+            "\
+0x05, 0x0B, // Usage Page (Telephony Page) [Global]
+0x09, 0x05, // Usage (Headset) [Local]
+0xA1, 0x01, // Collection (Application) [Main]
+
+// 6 bytes, 3 items
+"
+        );
+        assert_eq!(ReportFormatter::new().summary(false).format(&report).lines().count(), 3);
+    }
+}
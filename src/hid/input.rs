@@ -0,0 +1,324 @@
+use std::fmt;
+
+use super::assembler::strip_comments;
+
+/// A textual format that report descriptor bytes are shared in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// `usbhid-dump` output, a `001:004:000:DESCRIPTOR` header followed by hex lines.
+    UsbhidDump,
+    /// A C array, as in `0x05, 0x01,`.
+    CArray,
+    /// Hex bytes separated by spaces or commas, or runs of hex digits as `xxd -p` writes them.
+    PlainHex,
+    /// An `xxd` dump, as in `00000000: 0501 0902  ....`.
+    Xxd,
+    /// The `R:` line of `hid-recorder` output, the length followed by the bytes.
+    HidRecorder,
+}
+
+/// Error returned by [`parse_input`], with the 1-based line and column it was found at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: InputErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputErrorKind {
+    /// The token is not a hex byte.
+    InvalidToken(String),
+    /// The length of a `hid-recorder` line doesn't match its bytes.
+    LengthMismatch { expected: usize, got: usize },
+    /// No bytes were found.
+    Empty,
+}
+
+impl fmt::Display for InputErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputErrorKind::InvalidToken(token) => write!(f, "`{}` is not a hex byte", token),
+            InputErrorKind::LengthMismatch { expected, got } => {
+                write!(f, "The length is {}, but there are {} bytes", expected, got)
+            },
+            InputErrorKind::Empty => write!(f, "No descriptor bytes found"),
+        }
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.kind)
+    }
+}
+
+impl std::error::Error for InputError {}
+
+// A 0-based column and what went wrong there
+type LineError = (usize, InputErrorKind);
+
+/// Detects the format of the text, falling back to plain hex.
+pub fn detect_format(text: &str) -> InputFormat {
+    let lines = || text.lines().map(str::trim_start);
+
+    if lines().any(|line| line.starts_with("R:")) {
+        InputFormat::HidRecorder
+    } else if lines().any(is_usbhid_dump_header) {
+        InputFormat::UsbhidDump
+    } else if lines().any(is_xxd_line) {
+        InputFormat::Xxd
+    } else if text.contains("0x") || text.contains("0X") {
+        InputFormat::CArray
+    } else {
+        InputFormat::PlainHex
+    }
+}
+
+/// Parses report descriptor bytes from text, in the format [`detect_format`] finds.
+///
+/// `//`, `/* */` and `#` comments are ignored, except in xxd dumps, where only `#` lines are.
+/// Of several descriptors, as `usbhid-dump` writes for every interface, the first one is returned.
+pub fn parse_input(text: &str) -> Result<Vec<u8>, InputError> {
+    parse_input_as(text, detect_format(text))
+}
+
+/// Parses report descriptor bytes from text in the given format.
+pub fn parse_input_as(text: &str, format: InputFormat) -> Result<Vec<u8>, InputError> {
+    let mut bytes = vec![];
+    let mut in_comment = false;
+    // Whether the lines before the bytes, as a header or a C declaration, are over
+    let mut started = !has_start(text, format);
+
+    for (line_index, line) in text.lines().enumerate() {
+        // The ASCII column of xxd can hold `/*` or `//`, so only the hex columns are read there
+        let chars = match format {
+            InputFormat::Xxd => line.chars().collect(),
+            _ => strip_comments(line, &mut in_comment),
+        };
+        let to_error = |(column, kind): LineError| InputError {
+            line: line_index + 1,
+            column: column + 1,
+            kind,
+        };
+        let first = chars.iter().position(|c| !c.is_whitespace());
+        if first.is_some_and(|first| chars[first] == '#') {
+            continue;
+        }
+
+        match format {
+            InputFormat::UsbhidDump => {
+                let line: String = chars.iter().collect();
+                if is_usbhid_dump_header(line.trim_start()) {
+                    if started && !bytes.is_empty() {
+                        break;
+                    }
+                    started = true;
+                    continue;
+                }
+                if started {
+                    parse_hex_tokens(&chars, 0, chars.len(), &mut bytes).map_err(to_error)?;
+                }
+            },
+            InputFormat::CArray => {
+                let mut start = 0;
+                let mut end = chars.len();
+                if !started {
+                    match chars.iter().position(|c| *c == '{') {
+                        Some(open) => {
+                            started = true;
+                            start = open + 1;
+                        },
+                        None => continue,
+                    }
+                }
+                if let Some(close) = chars[start..].iter().position(|c| *c == '}') {
+                    end = start + close;
+                }
+                parse_hex_tokens(&chars, start, end, &mut bytes).map_err(to_error)?;
+                if end < chars.len() {
+                    break;
+                }
+            },
+            InputFormat::PlainHex => parse_hex_tokens(&chars, 0, chars.len(), &mut bytes).map_err(to_error)?,
+            InputFormat::Xxd => {
+                let line: String = chars.iter().collect();
+                if !is_xxd_line(line.trim_start()) {
+                    continue;
+                }
+                // The hex columns end where the ASCII column starts, two spaces later
+                let start = chars.iter().position(|c| *c == ':').unwrap() + 1;
+                let end = (start + 1..chars.len())
+                    .find(|index| chars[*index] == ' ' && chars[index - 1] == ' ')
+                    .map(|index| index - 1)
+                    .unwrap_or(chars.len());
+                parse_hex_tokens(&chars, start, end, &mut bytes).map_err(to_error)?;
+            },
+            InputFormat::HidRecorder => {
+                let start = match first {
+                    Some(first) if chars[first..].starts_with(&['R', ':']) => first + 2,
+                    _ => continue,
+                };
+                let mut tokens = get_tokens(&chars, start, chars.len());
+                let (length_column, length) = match tokens.next() {
+                    Some(token) => token,
+                    None => continue,
+                };
+                let expected = length
+                    .parse::<usize>()
+                    .map_err(|_| to_error((length_column, InputErrorKind::InvalidToken(length.clone()))))?;
+                let bytes_start = length_column + length.chars().count();
+                parse_hex_tokens(&chars, bytes_start, chars.len(), &mut bytes).map_err(to_error)?;
+                if bytes.len() != expected {
+                    return Err(to_error((start, InputErrorKind::LengthMismatch { expected, got: bytes.len() })));
+                }
+                break;
+            },
+        }
+    }
+
+    if bytes.is_empty() {
+        return Err(InputError {
+            line: 1,
+            column: 1,
+            kind: InputErrorKind::Empty,
+        });
+    }
+
+    Ok(bytes)
+}
+
+// Whether the bytes follow a header or a declaration
+fn has_start(text: &str, format: InputFormat) -> bool {
+    match format {
+        InputFormat::CArray => text.contains('{'),
+        InputFormat::UsbhidDump => true,
+        _ => false,
+    }
+}
+
+fn is_usbhid_dump_header(line: &str) -> bool {
+    let fields: Vec<&str> = line.splitn(4, ':').collect();
+    fields.len() == 4
+        && fields[..3].iter().all(|field| field.len() == 3 && field.chars().all(|c| c.is_ascii_digit()))
+        && fields[3].starts_with("DESCRIPTOR")
+}
+
+fn is_xxd_line(line: &str) -> bool {
+    match line.split_once(": ") {
+        Some((offset, _)) => offset.len() >= 7 && offset.chars().all(|c| c.is_ascii_hexdigit()),
+        None => false,
+    }
+}
+
+// Tokens separated by whitespace and commas, with their column
+fn get_tokens(chars: &[char], start: usize, end: usize) -> impl Iterator<Item = (usize, String)> + '_ {
+    let mut index = start;
+
+    std::iter::from_fn(move || {
+        while index < end && (chars[index].is_whitespace() || chars[index] == ',') {
+            index += 1;
+        }
+        if index == end {
+            return None;
+        }
+        let token_start = index;
+        while index < end && !chars[index].is_whitespace() && chars[index] != ',' {
+            index += 1;
+        }
+        Some((token_start, chars[token_start..index].iter().collect()))
+    })
+}
+
+// A token is one byte, with or without `0x`, or an even run of hex digits
+fn parse_hex_tokens(chars: &[char], start: usize, end: usize, bytes: &mut Vec<u8>) -> Result<(), LineError> {
+    for (column, token) in get_tokens(chars, start, end) {
+        let digits = token.strip_prefix("0x").or_else(|| token.strip_prefix("0X"));
+        let valid = match digits {
+            Some(digits) => (1..=2).contains(&digits.len()),
+            None => token.len() <= 2 || token.len() % 2 == 0,
+        };
+        let digits = digits.unwrap_or(&token);
+        if !valid || digits.is_empty() || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err((column, InputErrorKind::InvalidToken(token)));
+        }
+
+        if digits.len() <= 2 {
+            bytes.push(u8::from_str_radix(digits, 16).unwrap());
+        } else {
+            for pair in digits.as_bytes().chunks(2) {
+                bytes.push(u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_format, parse_input, InputErrorKind, InputFormat};
+
+    const BYTES: [u8; 8] = [0x05, 0x0B, 0x09, 0x05, 0xA1, 0x01, 0xC0, 0x00];
+
+    #[test]
+    fn parse_formats() {
+        let usbhid_dump = "\
+001:004:000:DESCRIPTOR         1357910009.758544
+ 05 0B 09 05 A1 01
+ C0 00
+
+001:004:001:DESCRIPTOR         1357910009.760000
+ 06 00 FF
+";
+        let c_array = "\
+#define HEADSET_LEN 8
+
+static const uint8_t headset[] = {
+    0x05, 0x0B,        // Usage Page (Telephony Page)
+    0x09, 0x05,        /* Usage (Headset) */
+    0xA1, 0x01, 0xC0, 0x00 };
+";
+        let plain = "05 0b 09 05\na1,01,c0,00";
+        let xxd_plain = "050b0905a101c000\n";
+        let xxd = "00000000: 050b 0905 a101 c000                      ..........\n";
+        let hid_recorder = "\
+# Jabra headset
+N: Jabra Link
+R: 8 05 0b 09 05 a1 01 c0 00
+I: 3 0b0e 2456
+";
+
+        assert_eq!(detect_format(usbhid_dump), InputFormat::UsbhidDump);
+        assert_eq!(detect_format(c_array), InputFormat::CArray);
+        assert_eq!(detect_format(plain), InputFormat::PlainHex);
+        assert_eq!(detect_format(xxd), InputFormat::Xxd);
+        assert_eq!(detect_format(hid_recorder), InputFormat::HidRecorder);
+
+        for text in [usbhid_dump, c_array, plain, xxd_plain, xxd, hid_recorder] {
+            assert_eq!(parse_input(text).unwrap(), BYTES.to_vec(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = parse_input("0x05, 0x0B,\n0x09, 0x105,").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 7));
+        assert_eq!(error.kind, InputErrorKind::InvalidToken("0x105".to_string()));
+
+        let error = parse_input("R: 130 05 0b").unwrap_err();
+        assert_eq!(error.kind, InputErrorKind::LengthMismatch { expected: 130, got: 2 });
+
+        assert_eq!(parse_input("// nothing\n").unwrap_err().kind, InputErrorKind::Empty);
+    }
+
+    #[test]
+    fn xxd_comment_characters() {
+        let xxd = "\
+00000000: 0509 2f2a 0101  ../*..
+00000006: 2f2f 0203       //..
+";
+        assert_eq!(detect_format(xxd), InputFormat::Xxd);
+        assert_eq!(parse_input(xxd).unwrap(), vec![0x05, 0x09, 0x2F, 0x2A, 0x01, 0x01, 0x2F, 0x2F, 0x02, 0x03]);
+    }
+}
@@ -0,0 +1,365 @@
+use std::collections::BTreeMap;
+
+use super::descriptor::{
+    DataPoint, DataType, GlobalState, HidItem, HidLocalTag, HidMainTag, ItemType, MainInputData, MainOutputData,
+    NullState, Report, Usage,
+};
+
+// Usage Minimum and Maximum ranges are expanded into single usages, up to this many
+const MAX_RANGE_USAGES: u32 = 0x10000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReportKind {
+    Input,
+    Output,
+    Feature,
+}
+
+/// The data flags of a field, Feature items share the flags of Output items.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldFlags {
+    Input(MainInputData),
+    Output(MainOutputData),
+}
+
+impl FieldFlags {
+    pub fn is_constant(&self) -> bool {
+        let item_type = match self {
+            FieldFlags::Input(data) => data.item_type,
+            FieldFlags::Output(data) => data.item_type,
+        };
+        item_type == ItemType::Constant
+    }
+
+    pub fn is_array(&self) -> bool {
+        let data_type = match self {
+            FieldFlags::Input(data) => data.data_type,
+            FieldFlags::Output(data) => data.data_type,
+        };
+        data_type == DataType::Array
+    }
+
+    pub fn is_relative(&self) -> bool {
+        let data_point = match self {
+            FieldFlags::Input(data) => data.data_point,
+            FieldFlags::Output(data) => data.data_point,
+        };
+        data_point == DataPoint::Relative
+    }
+
+    pub fn has_null_state(&self) -> bool {
+        let null_state = match self {
+            FieldFlags::Input(data) => data.null_state,
+            FieldFlags::Output(data) => data.null_state,
+        };
+        null_state == NullState::NullState
+    }
+}
+
+/// A field of a report, as declared by a single Input, Output or Feature item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportField {
+    // Offset from the start of the report data, not counting the Report ID byte
+    pub bit_offset: u32,
+    pub report_size: u32,
+    pub report_count: u32,
+    pub logical_minimum: i64,
+    pub logical_maximum: i64,
+    pub physical_minimum: i64,
+    pub physical_maximum: i64,
+    pub unit: u32,
+    pub unit_exponent: i32,
+    // Usages in declaration order, with Usage Minimum / Maximum ranges expanded
+    pub usages: Vec<Usage>,
+    pub flags: FieldFlags,
+}
+
+impl ReportField {
+    /// The length of the field in bits, saturating at `u32::MAX`.
+    pub fn bit_length(&self) -> u32 {
+        self.report_size.saturating_mul(self.report_count)
+    }
+
+    /// The usage of the value at `index` of a Variable field.
+    ///
+    /// When there are fewer usages than values, the last usage applies to the remaining values.
+    pub fn get_variable_usage(&self, index: u32) -> Option<Usage> {
+        self.usages.get(index as usize).or(self.usages.last()).copied()
+    }
+
+    /// The usage selected by `value` in an Array field.
+    pub fn get_array_usage(&self, value: i64) -> Option<Usage> {
+        if value < self.logical_minimum || value > self.logical_maximum {
+            return None;
+        }
+
+        self.usages.get((value - self.logical_minimum) as usize).copied()
+    }
+}
+
+/// The layout of one report, identified by its Report ID and kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportLayout {
+    pub report_id: Option<u8>,
+    pub kind: ReportKind,
+    pub fields: Vec<ReportField>,
+    // Length of the report data in bits, not counting the Report ID byte
+    pub bit_length: u32,
+}
+
+impl ReportLayout {
+    /// Whether the report is prefixed by its Report ID byte.
+    pub fn has_report_id(&self) -> bool {
+        self.report_id.is_some()
+    }
+
+    /// Length of the report in bytes, including the Report ID byte.
+    pub fn byte_length(&self) -> usize {
+        let data_length = self.bit_length.div_ceil(8) as usize;
+        if self.has_report_id() {
+            data_length + 1
+        } else {
+            data_length
+        }
+    }
+}
+
+/// Computes the layout of every report declared by a report descriptor, ordered by Report ID and kind.
+///
+/// Fields with a Report ID above 255, or that would make their report longer than `u32::MAX` bits,
+/// are skipped.
+pub fn get_report_layouts(report: &Report) -> Vec<ReportLayout> {
+    let mut layouts: BTreeMap<(Option<u8>, ReportKind), ReportLayout> = BTreeMap::new();
+    let mut local_usages: Vec<&HidItem> = vec![];
+
+    for item in &report.items {
+        if item.usage.is_some() {
+            local_usages.push(item);
+        }
+
+        let main_tag = match &item.main_tag {
+            Some(tag) => tag,
+            None => continue,
+        };
+        let (kind, flags) = match main_tag {
+            HidMainTag::Input(data) => (ReportKind::Input, FieldFlags::Input(data.clone())),
+            HidMainTag::Output(data) => (ReportKind::Output, FieldFlags::Output(data.clone())),
+            HidMainTag::Feature(data) => (ReportKind::Feature, FieldFlags::Output(data.clone())),
+            _ => {
+                // Local items only apply to the next main item
+                local_usages.clear();
+                continue;
+            },
+        };
+
+        let default_state = GlobalState::default();
+        let state = item.global_state.as_ref().unwrap_or(&default_state);
+        let report_id = match state.report_id.map(u8::try_from) {
+            Some(Ok(report_id)) => Some(report_id),
+            Some(Err(_)) => {
+                local_usages.clear();
+                continue;
+            },
+            None => None,
+        };
+        let layout = layouts.entry((report_id, kind)).or_insert_with(|| ReportLayout {
+            report_id,
+            kind,
+            fields: vec![],
+            bit_length: 0,
+        });
+
+        let (logical_minimum, logical_maximum) = state.logical_extents().unwrap_or((0, 0));
+        let (physical_minimum, physical_maximum) = state.physical_extents().unwrap_or((0, 0));
+        let field = ReportField {
+            bit_offset: layout.bit_length,
+            report_size: state.report_size.unwrap_or(0),
+            report_count: state.report_count.unwrap_or(0),
+            logical_minimum,
+            logical_maximum,
+            physical_minimum,
+            physical_maximum,
+            unit: state.unit.unwrap_or(0) as u32,
+            unit_exponent: state.unit_exponent.unwrap_or(0),
+            usages: get_field_usages(&local_usages, state.usage_page.unwrap_or(0)),
+            flags,
+        };
+        local_usages.clear();
+
+        let bit_length = field.report_size.checked_mul(field.report_count);
+        match bit_length.and_then(|bit_length| layout.bit_length.checked_add(bit_length)) {
+            Some(total) if total > layout.bit_length => {
+                layout.bit_length = total;
+                layout.fields.push(field);
+            },
+            _ => {},
+        }
+    }
+
+    layouts.into_values().collect()
+}
+
+/// Finds the layout of a report by its Report ID and kind.
+pub fn find_report_layout(layouts: &[ReportLayout], report_id: Option<u8>, kind: ReportKind) -> Option<&ReportLayout> {
+    layouts
+        .iter()
+        .find(|layout| layout.report_id == report_id && layout.kind == kind)
+}
+
+// Reads up to 64 bits starting at `bit_offset`, least significant bit first
+pub(crate) fn get_bits(data: &[u8], bit_offset: u32, bit_length: u32) -> u64 {
+    let mut value: u64 = 0;
+
+    for i in 0..bit_length.min(64) {
+        let bit = bit_offset + i;
+        let byte = data.get((bit / 8) as usize).copied().unwrap_or(0);
+        if byte & (1 << (bit % 8)) != 0 {
+            value |= 1 << i;
+        }
+    }
+
+    value
+}
+
+// Writes up to 64 bits starting at `bit_offset`, least significant bit first
+pub(crate) fn set_bits(data: &mut [u8], bit_offset: u32, bit_length: u32, value: u64) {
+    for i in 0..bit_length.min(64) {
+        let bit = bit_offset + i;
+        let byte = match data.get_mut((bit / 8) as usize) {
+            Some(byte) => byte,
+            None => return,
+        };
+        if value & (1 << i) != 0 {
+            *byte |= 1 << (bit % 8);
+        } else {
+            *byte &= !(1 << (bit % 8));
+        }
+    }
+}
+
+fn get_field_usages(items: &[&HidItem], usage_page: u32) -> Vec<Usage> {
+    let mut usages: Vec<Usage> = vec![];
+    let mut minimum: Option<Usage> = None;
+
+    for item in items {
+        // The Usage Page in effect at the main item applies to usages that don't select their own
+        let usage = match item.usage {
+            Some(usage) if item.is_extended_usage() => usage,
+            Some(usage) => Usage::new(usage_page, usage.id),
+            None => continue,
+        };
+
+        match &item.local_tag {
+            Some(HidLocalTag::Usage(_)) => usages.push(usage),
+            Some(HidLocalTag::UsageMinimum(_)) => minimum = Some(usage),
+            Some(HidLocalTag::UsageMaximum(_)) => {
+                if let Some(minimum) = minimum.take() {
+                    let maximum = usage.id.min(minimum.id.saturating_add(MAX_RANGE_USAGES - 1));
+                    for id in minimum.id..=maximum {
+                        usages.push(Usage::new(minimum.page, id));
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+
+    usages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_report_layout, get_report_layouts, ReportKind};
+    use crate::hid::descriptor::{self, Usage};
+
+    const TELEPHONY: [u8; 126] = [0x05, 0x0B, 0x09, 0x05, 0xA1, 0x01, 0x85, 0x02, 0x05, 0x0B, 0x15, 0x00, 0x25, 0x01, 0x09, 0x20, 0x09, 0x97, 0x09, 0x2A, 0x75, 0x01, 0x95, 0x03, 0x81, 0x23, 0x09, 0x2F, 0x09, 0x21, 0x09, 0x24, 0x09, 0x50, 0x75, 0x01, 0x95, 0x04, 0x81, 0x07, 0x09, 0x06, 0xA1, 0x02, 0x19, 0xB0, 0x29, 0xBB, 0x15, 0x00, 0x25, 0x0C, 0x75, 0x04, 0x95, 0x01, 0x81, 0x40, 0xC0, 0x09, 0x07, 0x15, 0x00, 0x25, 0x01, 0x05, 0x09, 0x75, 0x01, 0x95, 0x01, 0x81, 0x02, 0x75, 0x01, 0x95, 0x04, 0x81, 0x01, 0x05, 0x08, 0x15, 0x00, 0x25, 0x01, 0x09, 0x17, 0x09, 0x1E, 0x09, 0x09, 0x09, 0x18, 0x09, 0x20, 0x09, 0x21, 0x09, 0x2A, 0x75, 0x01, 0x95, 0x07, 0x91, 0x22, 0x05, 0x0B, 0x15, 0x00, 0x25, 0x01, 0x09, 0x9E, 0x75, 0x01, 0x95, 0x01, 0x91, 0x22, 0x75, 0x01, 0x95, 0x08, 0x91, 0x01, 0xC0];
+
+    #[test]
+    fn telephony_layouts() {
+        let report = descriptor::try_get_descriptor_report(&TELEPHONY).unwrap();
+        let layouts = get_report_layouts(&report);
+
+        assert_eq!(layouts.len(), 2);
+
+        let input = find_report_layout(&layouts, Some(2), ReportKind::Input).unwrap();
+        assert!(input.has_report_id());
+        assert_eq!(input.bit_length, 16);
+        assert_eq!(input.byte_length(), 3);
+        assert_eq!(input.fields.len(), 5);
+
+        let hook_switch = &input.fields[0];
+        assert_eq!(hook_switch.bit_offset, 0);
+        assert_eq!(hook_switch.report_count, 3);
+        assert_eq!(hook_switch.usages, vec![Usage::new(0x0B, 0x20), Usage::new(0x0B, 0x97), Usage::new(0x0B, 0x2A)]);
+        assert!(hook_switch.flags.is_constant());
+
+        let key_pad = &input.fields[2];
+        assert_eq!(key_pad.bit_offset, 7);
+        assert_eq!(key_pad.report_size, 4);
+        assert_eq!((key_pad.logical_minimum, key_pad.logical_maximum), (0, 12));
+        assert_eq!(key_pad.usages.len(), 12);
+        assert!(key_pad.flags.is_array());
+        assert!(key_pad.flags.has_null_state());
+        assert_eq!(key_pad.get_array_usage(1), Some(Usage::new(0x0B, 0xB1)));
+        assert_eq!(key_pad.get_array_usage(13), None);
+
+        // The Usage Page in effect at the Input item applies, not the one at the Usage item
+        let programmable_button = &input.fields[3];
+        assert_eq!(programmable_button.bit_offset, 11);
+        assert_eq!(programmable_button.usages, vec![Usage::new(0x09, 0x07)]);
+
+        let output = find_report_layout(&layouts, Some(2), ReportKind::Output).unwrap();
+        assert_eq!(output.byte_length(), 3);
+        assert_eq!(output.fields.len(), 3);
+        assert_eq!(output.fields[1].bit_offset, 7);
+        assert_eq!(output.fields[1].get_variable_usage(0), Some(Usage::new(0x0B, 0x9E)));
+        assert_eq!(output.fields[0].get_variable_usage(6), Some(Usage::new(0x08, 0x2A)));
+
+        assert!(find_report_layout(&layouts, Some(2), ReportKind::Feature).is_none());
+    }
+
+    #[test]
+    fn layout_without_report_id() {
+        let bytes = [
+            0x05, 0x01, // Usage Page (Generic Desktop Page)
+            0x09, 0x30, // Usage (X)
+            0x15, 0x81, // Logical Minimum (-127)
+            0x25, 0x7F, // Logical Maximum (127)
+            0x75, 0x08, // Report Size (8)
+            0x95, 0x02, // Report Count (2)
+            0x81, 0x06, // Input (Data, Variable, Relative)
+        ];
+        let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+        let layouts = get_report_layouts(&report);
+
+        assert_eq!(layouts.len(), 1);
+        assert!(!layouts[0].has_report_id());
+        assert_eq!(layouts[0].byte_length(), 2);
+        assert_eq!(layouts[0].fields[0].logical_minimum, -127);
+        assert!(layouts[0].fields[0].flags.is_relative());
+        assert_eq!(layouts[0].fields[0].get_variable_usage(1), Some(Usage::new(0x01, 0x30)));
+    }
+
+    #[test]
+    fn invalid_fields_skipped() {
+        let bytes = [
+            0x75, 0x08, // Report Size (8)
+            0x95, 0x01, // Report Count (1)
+            0x81, 0x02, // Input (Data, Variable, Absolute)
+            0x77, 0x00, 0x00, 0x01, 0x00, // Report Size (65536)
+            0x97, 0x00, 0x00, 0x01, 0x00, // Report Count (65536)
+            0x81, 0x02, // Input (Data, Variable, Absolute)
+            0x75, 0x08, // Report Size (8)
+            0x95, 0x01, // Report Count (1)
+            0x86, 0x00, 0x01, // Report ID (256)
+            0x81, 0x02, // Input (Data, Variable, Absolute)
+        ];
+        let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+        let layouts = get_report_layouts(&report);
+
+        assert_eq!(layouts.len(), 1);
+        assert_eq!(layouts[0].report_id, None);
+        assert_eq!(layouts[0].fields.len(), 1);
+        assert_eq!(layouts[0].bit_length, 8);
+    }
+}
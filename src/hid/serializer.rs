@@ -0,0 +1,260 @@
+use std::fmt;
+
+use super::descriptor::{HidGlobalTag, HidItem, HidItemType, HidLocalTag, HidMainTag};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerializeError {
+    /// Reserved tags and collection types don't keep their value, so they can't be encoded.
+    Reserved,
+    /// Short items hold 0, 1, 2 or 4 data bytes.
+    InvalidSize(u8),
+    /// The value doesn't fit in the requested number of data bytes.
+    ValueTooLarge { size_bytes: u8 },
+}
+
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerializeError::Reserved => write!(f, "Reserved items can't be encoded"),
+            SerializeError::InvalidSize(size_bytes) => write!(f, "Short items can't hold {} data bytes", size_bytes),
+            SerializeError::ValueTooLarge { size_bytes } => write!(f, "The value doesn't fit in {} data bytes", size_bytes),
+        }
+    }
+}
+
+impl std::error::Error for SerializeError {}
+
+const ITEM_TYPE_MAIN: u8 = 0;
+const ITEM_TYPE_GLOBAL: u8 = 1;
+const ITEM_TYPE_LOCAL: u8 = 2;
+
+// Data bits that no Input, or Output and Feature, flag is assigned to
+const INPUT_RESERVED_BITS: u32 = 0xFFFF_FE80;
+const OUTPUT_RESERVED_BITS: u32 = 0xFFFF_FE00;
+
+enum Data {
+    Unsigned(u32),
+    Signed(i32),
+    // Encoded as a 4 bit two's complement nibble when it fits, in any data size
+    UnitExponent(i32),
+}
+
+/// Encodes a main item, in `size_bytes` data bytes or else the smallest size that fits.
+///
+/// End Collection is encoded without data, other items get at least one data byte.
+pub fn encode_main_tag(tag: &HidMainTag, size_bytes: Option<u8>) -> Result<Vec<u8>, SerializeError> {
+    encode_main_tag_with_reserved(tag, 0, size_bytes)
+}
+
+// Encodes a main item, setting the reserved bits of `reserved_bits` in the flags of Input, Output and Feature items
+fn encode_main_tag_with_reserved(tag: &HidMainTag, reserved_bits: u32, size_bytes: Option<u8>) -> Result<Vec<u8>, SerializeError> {
+    let (tag, data) = match tag {
+        HidMainTag::Input(data) => (0b1000, data.to_bits() | (reserved_bits & INPUT_RESERVED_BITS)),
+        HidMainTag::Output(data) => (0b1001, data.to_bits() | (reserved_bits & OUTPUT_RESERVED_BITS)),
+        HidMainTag::Feature(data) => (0b1011, data.to_bits() | (reserved_bits & OUTPUT_RESERVED_BITS)),
+        HidMainTag::Collection(collection_type) => match collection_type.to_value() {
+            Some(value) => (0b1010, value as u32),
+            None => return Err(SerializeError::Reserved),
+        },
+        HidMainTag::EndCollection => return encode_short_item(0b1100, ITEM_TYPE_MAIN, Data::Unsigned(0), size_bytes.or(Some(0))),
+        HidMainTag::Reserved => return Err(SerializeError::Reserved),
+    };
+
+    encode_short_item(tag, ITEM_TYPE_MAIN, Data::Unsigned(data), size_bytes)
+}
+
+/// Encodes a global item, in `size_bytes` data bytes or else the smallest size that fits.
+///
+/// Push and Pop are encoded without data, other items get at least one data byte.
+pub fn encode_global_tag(tag: &HidGlobalTag, size_bytes: Option<u8>) -> Result<Vec<u8>, SerializeError> {
+    let (tag, data) = match tag {
+        HidGlobalTag::UsagePage(value) => (0b0000, Data::Unsigned(*value)),
+        HidGlobalTag::LogicalMinimum(value) => (0b0001, Data::Signed(*value)),
+        HidGlobalTag::LogicalMaximum(value) => (0b0010, Data::Signed(*value)),
+        HidGlobalTag::PhysicalMinimum(value) => (0b0011, Data::Signed(*value)),
+        HidGlobalTag::PhysicalMaximum(value) => (0b0100, Data::Signed(*value)),
+        HidGlobalTag::UnitExponent(value) => (0b0101, Data::UnitExponent(*value)),
+        HidGlobalTag::Unit(value) => (0b0110, Data::Unsigned(*value as u32)),
+        HidGlobalTag::ReportSize(value) => (0b0111, Data::Unsigned(*value)),
+        HidGlobalTag::ReportId(value) => (0b1000, Data::Unsigned(*value)),
+        HidGlobalTag::ReportCount(value) => (0b1001, Data::Unsigned(*value)),
+        HidGlobalTag::Push(value) | HidGlobalTag::Pop(value) => {
+            let tag = if matches!(tag, HidGlobalTag::Push(_)) { 0b1010 } else { 0b1011 };
+            let size_bytes = if *value == 0 { size_bytes.or(Some(0)) } else { size_bytes };
+            return encode_short_item(tag, ITEM_TYPE_GLOBAL, Data::Signed(*value), size_bytes);
+        },
+        HidGlobalTag::Reserved(_) => return Err(SerializeError::Reserved),
+    };
+
+    encode_short_item(tag, ITEM_TYPE_GLOBAL, data, size_bytes)
+}
+
+/// Encodes a local item, in `size_bytes` data bytes or else the smallest size that fits.
+///
+/// An extended usage, with the usage page in its high 16 bits, needs 4 data bytes.
+pub fn encode_local_tag(tag: &HidLocalTag, size_bytes: Option<u8>) -> Result<Vec<u8>, SerializeError> {
+    let (tag, value) = match tag {
+        HidLocalTag::Usage(value) => (0b0000, *value),
+        HidLocalTag::UsageMinimum(value) => (0b0001, *value),
+        HidLocalTag::UsageMaximum(value) => (0b0010, *value),
+        HidLocalTag::DesignatorIndex(value) => (0b0011, *value),
+        HidLocalTag::DesignatorMinimum(value) => (0b0100, *value),
+        HidLocalTag::DesignatorMaximum(value) => (0b0101, *value),
+        HidLocalTag::StringIndex(value) => (0b0111, *value),
+        HidLocalTag::StringMinimum(value) => (0b1000, *value),
+        HidLocalTag::StringMaximum(value) => (0b1001, *value),
+        HidLocalTag::Delimiter(value) => (0b1010, *value),
+        HidLocalTag::Reserved(_) => return Err(SerializeError::Reserved),
+    };
+
+    encode_short_item(tag, ITEM_TYPE_LOCAL, Data::Unsigned(value), size_bytes)
+}
+
+/// Encodes an item from its tags, keeping its data size if the value still fits.
+///
+/// The reserved flag bits of main items, and the signed or nibble form of a Unit Exponent, are kept
+/// from the item's bytes. Long items, and items whose tags can't be encoded, are copied from their bytes.
+pub fn encode_item(item: &HidItem) -> Vec<u8> {
+    let original_data = item
+        .bytes
+        .iter()
+        .skip(1)
+        .take(4)
+        .enumerate()
+        .fold(0u32, |value, (i, byte)| value | (*byte as u32) << (i * 8));
+    let encode = |size_bytes: Option<u8>| match &item.item_type {
+        HidItemType::Main => item
+            .main_tag
+            .as_ref()
+            .map(|tag| encode_main_tag_with_reserved(tag, original_data, size_bytes)),
+        HidItemType::Global => item.global_tag.as_ref().map(|tag| match tag {
+            HidGlobalTag::UnitExponent(value) if original_data & 0xFFFF_FFF0 != 0 => {
+                encode_short_item(0b0101, ITEM_TYPE_GLOBAL, Data::Signed(*value), size_bytes)
+            },
+            _ => encode_global_tag(tag, size_bytes),
+        }),
+        HidItemType::Local => item.local_tag.as_ref().map(|tag| encode_local_tag(tag, size_bytes)),
+        HidItemType::Long | HidItemType::Unknown => None,
+    };
+
+    match encode(Some(item.size_bytes)) {
+        Some(Ok(bytes)) => bytes,
+        Some(Err(SerializeError::ValueTooLarge { .. })) => match encode(None) {
+            Some(Ok(bytes)) => bytes,
+            _ => item.bytes.clone(),
+        },
+        _ => item.bytes.clone(),
+    }
+}
+
+fn encode_short_item(tag: u8, item_type: u8, data: Data, size_bytes: Option<u8>) -> Result<Vec<u8>, SerializeError> {
+    let size_bytes = match size_bytes {
+        Some(size_bytes) => {
+            if !matches!(size_bytes, 0 | 1 | 2 | 4) {
+                return Err(SerializeError::InvalidSize(size_bytes));
+            }
+            if !fits(&data, size_bytes) {
+                return Err(SerializeError::ValueTooLarge { size_bytes });
+            }
+            size_bytes
+        },
+        None => [1, 2, 4].into_iter().find(|size_bytes| fits(&data, *size_bytes)).unwrap_or(4),
+    };
+
+    let value = match data {
+        Data::Unsigned(value) => value,
+        Data::Signed(value) => value as u32,
+        Data::UnitExponent(value) if (-8..=7).contains(&value) => value as u32 & 0x0F,
+        Data::UnitExponent(value) => value as u32,
+    };
+    let size_code = match size_bytes {
+        0 => 0,
+        1 => 1,
+        2 => 2,
+        _ => 3,
+    };
+
+    let mut bytes = vec![(tag << 4) | (item_type << 2) | size_code];
+    bytes.extend_from_slice(&value.to_le_bytes()[..size_bytes as usize]);
+    Ok(bytes)
+}
+
+fn fits(data: &Data, size_bytes: u8) -> bool {
+    match data {
+        Data::Unsigned(value) => match size_bytes {
+            0 => *value == 0,
+            1 => *value <= 0xFF,
+            2 => *value <= 0xFFFF,
+            _ => true,
+        },
+        Data::Signed(value) | Data::UnitExponent(value) => match size_bytes {
+            0 => *value == 0,
+            1 => i8::try_from(*value).is_ok(),
+            2 => i16::try_from(*value).is_ok(),
+            _ => true,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_global_tag, encode_local_tag, encode_main_tag, SerializeError};
+    use crate::hid::descriptor::{self, CollectionType, HidGlobalTag, HidLocalTag, HidMainTag, MainInputData};
+
+    #[test]
+    fn round_trip() {
+        let bytes = [
+            0x05, 0x0B,                   // Usage Page (Telephony Page)
+            0x09, 0x05,                   // Usage (Headset)
+            0xA1, 0x01,                   // Collection (Application)
+            0x85, 0x02,                   // Report ID (2)
+            0x15, 0x81,                   // Logical Minimum (-127)
+            0x26, 0xFF, 0x00,             // Logical Maximum (255)
+            0x55, 0x0E,                   // Unit Exponent (-2)
+            0x56, 0x0E, 0x00,             // Unit Exponent (-2)
+            0x55, 0xFE,                   // Unit Exponent (-2)
+            0x67, 0x01, 0x10, 0x00, 0x00, // Unit (4097)
+            0x0B, 0xE9, 0x00, 0x0C, 0x00, // Usage (Consumer Page: Volume Increment)
+            0x75, 0x10,                   // Report Size (16)
+            0x95, 0x01,                   // Report Count (1)
+            0x82, 0x02, 0x01,             // Input (Data, Variable, Absolute, Buffered Bytes)
+            0x81, 0x82,                   // Input (Data, Variable, Absolute)
+            0x92, 0x02, 0x06,             // Output (Data, Variable, Absolute)
+            0xA4,                         // Push
+            0xB4,                         // Pop
+            0xFE, 0x01, 0x42, 0x07,       // Long Item (0x42, 1 bytes)
+            0xC0,                         // End Collection
+        ];
+        let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+
+        assert_eq!(report.to_bytes(), bytes.to_vec());
+    }
+
+    #[test]
+    fn smallest_size() {
+        let input = HidMainTag::Input(MainInputData::from_bits(0x02));
+        assert_eq!(encode_main_tag(&input, None), Ok(vec![0x81, 0x02]));
+        assert_eq!(encode_main_tag(&HidMainTag::Collection(CollectionType::Application), None), Ok(vec![0xA1, 0x01]));
+        assert_eq!(encode_main_tag(&HidMainTag::EndCollection, None), Ok(vec![0xC0]));
+
+        assert_eq!(encode_global_tag(&HidGlobalTag::LogicalMinimum(-1), None), Ok(vec![0x15, 0xFF]));
+        assert_eq!(encode_global_tag(&HidGlobalTag::LogicalMaximum(255), None), Ok(vec![0x26, 0xFF, 0x00]));
+        assert_eq!(encode_global_tag(&HidGlobalTag::UsagePage(0xFF00), None), Ok(vec![0x06, 0x00, 0xFF]));
+        assert_eq!(encode_global_tag(&HidGlobalTag::UnitExponent(-3), None), Ok(vec![0x55, 0x0D]));
+        assert_eq!(encode_global_tag(&HidGlobalTag::Push(0), None), Ok(vec![0xA4]));
+
+        assert_eq!(encode_local_tag(&HidLocalTag::Usage(0x000C00E9), None), Ok(vec![0x0B, 0xE9, 0x00, 0x0C, 0x00]));
+    }
+
+    #[test]
+    fn chosen_size() {
+        assert_eq!(encode_global_tag(&HidGlobalTag::ReportCount(2), Some(2)), Ok(vec![0x96, 0x02, 0x00]));
+        assert_eq!(encode_global_tag(&HidGlobalTag::LogicalMinimum(0), Some(0)), Ok(vec![0x14]));
+        assert_eq!(
+            encode_global_tag(&HidGlobalTag::LogicalMaximum(255), Some(1)),
+            Err(SerializeError::ValueTooLarge { size_bytes: 1 })
+        );
+        assert_eq!(encode_local_tag(&HidLocalTag::Usage(1), Some(3)), Err(SerializeError::InvalidSize(3)));
+        assert_eq!(encode_main_tag(&HidMainTag::Reserved, None), Err(SerializeError::Reserved));
+    }
+}
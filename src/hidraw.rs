@@ -0,0 +1,334 @@
+//! Talks to HID devices through Linux `/dev/hidrawN` nodes.
+//!
+//! Unlike the rusb functions, the kernel driver stays attached, so a headset keeps working in
+//! an active call while its reports are read.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+
+use crate::hid::decoder::{decode_input_report, decode_report, DecodedReport};
+use crate::hid::descriptor::Usage;
+use crate::hid::encoder::encode_report;
+use crate::hid::layout::{ReportKind, ReportLayout};
+use crate::sysfs::Bus;
+
+// From linux/hidraw.h
+const HID_MAX_DESCRIPTOR_SIZE: usize = 4096;
+
+// The _IOC encoding of asm-generic/ioctl.h, used by x86, ARM, RISC-V and most others
+#[cfg(not(any(
+    target_arch = "powerpc",
+    target_arch = "powerpc64",
+    target_arch = "mips",
+    target_arch = "mips64",
+    target_arch = "mips32r6",
+    target_arch = "mips64r6",
+    target_arch = "sparc",
+    target_arch = "sparc64"
+)))]
+mod ioc {
+    pub const WRITE: u64 = 1;
+    pub const READ: u64 = 2;
+    pub const DIRECTION_SHIFT: u64 = 30;
+}
+
+// PowerPC, MIPS and SPARC have a 3 bit direction and a 13 bit size
+#[cfg(any(
+    target_arch = "powerpc",
+    target_arch = "powerpc64",
+    target_arch = "mips",
+    target_arch = "mips64",
+    target_arch = "mips32r6",
+    target_arch = "mips64r6",
+    target_arch = "sparc",
+    target_arch = "sparc64"
+))]
+mod ioc {
+    pub const WRITE: u64 = 4;
+    pub const READ: u64 = 2;
+    pub const DIRECTION_SHIFT: u64 = 29;
+}
+
+use ioc::{READ as IOC_READ, WRITE as IOC_WRITE};
+
+const fn ioc(direction: u64, number: u64, size: usize) -> u64 {
+    (direction << ioc::DIRECTION_SHIFT) | ((size as u64) << 16) | ((b'H' as u64) << 8) | number
+}
+
+/// `HIDIOCGRDESCSIZE`, the size of the report descriptor as an int.
+pub const HIDIOCGRDESCSIZE: u64 = ioc(IOC_READ, 0x01, 4);
+/// `HIDIOCGRDESC`, a `hidraw_report_descriptor` holding the size and up to 4096 bytes.
+pub const HIDIOCGRDESC: u64 = ioc(IOC_READ, 0x02, 4 + HID_MAX_DESCRIPTOR_SIZE);
+/// `HIDIOCGRAWINFO`, a `hidraw_devinfo` holding the bus type, vendor ID and product ID.
+pub const HIDIOCGRAWINFO: u64 = ioc(IOC_READ, 0x03, 8);
+
+/// `HIDIOCSFEATURE`, sets a feature report of `length` bytes, starting with the Report ID.
+pub const fn hidiocsfeature(length: usize) -> u64 {
+    ioc(IOC_WRITE | IOC_READ, 0x06, length)
+}
+
+/// `HIDIOCGFEATURE`, gets a feature report of up to `length` bytes, starting with the Report ID.
+pub const fn hidiocgfeature(length: usize) -> u64 {
+    ioc(IOC_WRITE | IOC_READ, 0x07, length)
+}
+
+/// The file operations of a hidraw node, so that tests can use a fake one.
+pub trait HidrawIo {
+    /// Runs an ioctl with `data` as its argument, returning its result.
+    fn ioctl(&mut self, request: u64, data: &mut [u8]) -> io::Result<i32>;
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize>;
+    fn write(&mut self, data: &[u8]) -> io::Result<usize>;
+}
+
+/// An opened `/dev/hidrawN` node.
+#[derive(Debug)]
+pub struct HidrawFile(File);
+
+impl HidrawIo for HidrawFile {
+    fn ioctl(&mut self, request: u64, data: &mut [u8]) -> io::Result<i32> {
+        // The request encodes the size of the argument, which the caller sized data for
+        let result = unsafe { libc::ioctl(self.0.as_raw_fd(), request as _, data.as_mut_ptr()) };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(result)
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buffer)
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.0.write(data)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceInfo {
+    pub bus: Bus,
+    pub vendor_id: u16,
+    pub product_id: u16,
+}
+
+#[derive(Debug)]
+pub struct Hidraw<T: HidrawIo = HidrawFile> {
+    io: T,
+}
+
+impl Hidraw<HidrawFile> {
+    /// Opens a hidraw node, as in `/dev/hidraw3`, for reading and writing.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        Ok(Hidraw::new(HidrawFile(file)))
+    }
+}
+
+impl<T: HidrawIo> Hidraw<T> {
+    pub fn new(io: T) -> Self {
+        Hidraw { io }
+    }
+
+    pub fn report_descriptor(&mut self) -> io::Result<Vec<u8>> {
+        let mut size = [0u8; 4];
+        self.io.ioctl(HIDIOCGRDESCSIZE, &mut size)?;
+        let size = (i32::from_ne_bytes(size) as usize).min(HID_MAX_DESCRIPTOR_SIZE);
+
+        let mut descriptor = vec![0u8; 4 + HID_MAX_DESCRIPTOR_SIZE];
+        descriptor[..4].copy_from_slice(&(size as u32).to_ne_bytes());
+        self.io.ioctl(HIDIOCGRDESC, &mut descriptor)?;
+
+        Ok(descriptor[4..4 + size].to_vec())
+    }
+
+    pub fn device_info(&mut self) -> io::Result<DeviceInfo> {
+        let mut info = [0u8; 8];
+        self.io.ioctl(HIDIOCGRAWINFO, &mut info)?;
+
+        let bus = u32::from_ne_bytes([info[0], info[1], info[2], info[3]]);
+        Ok(DeviceInfo {
+            bus: Bus::from_value(bus as u16),
+            vendor_id: u16::from_ne_bytes([info[4], info[5]]),
+            product_id: u16::from_ne_bytes([info[6], info[7]]),
+        })
+    }
+
+    /// Gets a feature report of up to `length` bytes, including the Report ID byte.
+    ///
+    /// Devices without Report IDs take and return a Report ID of 0.
+    pub fn get_feature_report(&mut self, report_id: u8, length: usize) -> io::Result<Vec<u8>> {
+        let mut report = vec![0u8; length.max(1)];
+        report[0] = report_id;
+        let received = self.io.ioctl(hidiocgfeature(report.len()), &mut report)?;
+
+        report.truncate(received as usize);
+        Ok(report)
+    }
+
+    /// Sets a feature report, starting with its Report ID byte, or 0 for devices without Report IDs.
+    pub fn set_feature_report(&mut self, report: &[u8]) -> io::Result<()> {
+        let mut report = report.to_vec();
+        self.io.ioctl(hidiocsfeature(report.len()), &mut report)?;
+        Ok(())
+    }
+
+    /// Reads an input report, including its Report ID byte if the device uses Report IDs.
+    pub fn read_report(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        self.io.read(buffer)
+    }
+
+    /// Writes an output report, starting with its Report ID byte, or 0 for devices without Report IDs.
+    pub fn write_report(&mut self, report: &[u8]) -> io::Result<()> {
+        self.io.write(report)?;
+        Ok(())
+    }
+
+    /// Reads an input report and decodes it with the layouts of the report descriptor.
+    pub fn read_input<'a>(&mut self, layouts: &'a [ReportLayout]) -> io::Result<DecodedReport<'a>> {
+        let length = layouts
+            .iter()
+            .filter(|layout| layout.kind == ReportKind::Input)
+            .map(|layout| layout.byte_length())
+            .max()
+            .unwrap_or(0);
+        let mut report = vec![0u8; length.max(1)];
+        let received = self.read_report(&mut report)?;
+
+        decode_input_report(layouts, &report[..received]).map_err(invalid_data)
+    }
+
+    /// Gets a feature report and decodes it with its layout.
+    pub fn get_feature<'a>(&mut self, layout: &'a ReportLayout) -> io::Result<DecodedReport<'a>> {
+        let report_id = layout.report_id.unwrap_or(0);
+        let length = layout.byte_length() + usize::from(!layout.has_report_id());
+        let report = self.get_feature_report(report_id, length)?;
+
+        let report = if layout.has_report_id() { &report[..] } else { &report[1.min(report.len())..] };
+        decode_report(layout, report).map_err(invalid_data)
+    }
+
+    /// Encodes a feature report from usage values and sets it.
+    pub fn set_feature(&mut self, layout: &ReportLayout, values: &[(Usage, i64)]) -> io::Result<()> {
+        let report = self.encode(layout, values)?;
+        self.set_feature_report(&report)
+    }
+
+    /// Encodes an output report from usage values and writes it.
+    pub fn write_output(&mut self, layout: &ReportLayout, values: &[(Usage, i64)]) -> io::Result<()> {
+        let report = self.encode(layout, values)?;
+        self.write_report(&report)
+    }
+
+    // hidraw wants a Report ID byte of 0 for devices without Report IDs
+    fn encode(&self, layout: &ReportLayout, values: &[(Usage, i64)]) -> io::Result<Vec<u8>> {
+        let mut report = encode_report(layout, values).map_err(invalid_data)?;
+        if !layout.has_report_id() {
+            report.insert(0, 0);
+        }
+        Ok(report)
+    }
+}
+
+fn invalid_data(error: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::{hidiocgfeature, hidiocsfeature, Hidraw, HidrawIo, HIDIOCGRAWINFO, HIDIOCGRDESC, HIDIOCGRDESCSIZE};
+    use crate::hid::descriptor::{self, Usage};
+    use crate::hid::layout::{find_report_layout, get_report_layouts, ReportKind};
+    use crate::sysfs::Bus;
+
+    const DESCRIPTOR: [u8; 31] = [
+        0x05, 0x0B, // Usage Page (Telephony Page)
+        0x09, 0x05, // Usage (Headset)
+        0xA1, 0x01, // Collection (Application)
+        0x85, 0x02, // Report ID (2)
+        0x15, 0x00, // Logical Minimum (0)
+        0x25, 0x01, // Logical Maximum (1)
+        0x09, 0x20, // Usage (Hook Switch)
+        0x75, 0x01, // Report Size (1)
+        0x95, 0x01, // Report Count (1)
+        0x81, 0x02, // Input (Data, Variable, Absolute)
+        0x95, 0x07, // Report Count (7)
+        0x81, 0x01, // Input (Constant, Array, Absolute)
+        0x09, 0x9E, // Usage (Ringer)
+        0x95, 0x08, // Report Count (8)
+        0xB1, 0x02, // Feature (Data, Variable, Absolute)
+        0xC0,       // End Collection
+    ];
+
+    #[derive(Default)]
+    struct FakeHidraw {
+        feature: Vec<u8>,
+        written: Vec<Vec<u8>>,
+    }
+
+    impl HidrawIo for FakeHidraw {
+        fn ioctl(&mut self, request: u64, data: &mut [u8]) -> io::Result<i32> {
+            match request {
+                HIDIOCGRDESCSIZE => data.copy_from_slice(&(DESCRIPTOR.len() as i32).to_ne_bytes()),
+                HIDIOCGRDESC => data[4..4 + DESCRIPTOR.len()].copy_from_slice(&DESCRIPTOR),
+                HIDIOCGRAWINFO => {
+                    data[..4].copy_from_slice(&3u32.to_ne_bytes());
+                    data[4..6].copy_from_slice(&0x0B0Eu16.to_ne_bytes());
+                    data[6..].copy_from_slice(&0x2456u16.to_ne_bytes());
+                },
+                _ if request == hidiocgfeature(data.len()) => {
+                    data[..self.feature.len()].copy_from_slice(&self.feature);
+                    return Ok(self.feature.len() as i32);
+                },
+                _ if request == hidiocsfeature(data.len()) => self.feature = data.to_vec(),
+                _ => return Err(io::Error::from_raw_os_error(libc::EINVAL)),
+            }
+            Ok(0)
+        }
+
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            buffer[..2].copy_from_slice(&[0x02, 0x01]);
+            Ok(2)
+        }
+
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            self.written.push(data.to_vec());
+            Ok(data.len())
+        }
+    }
+
+    #[test]
+    fn ioctl_numbers() {
+        // Reading sets the top bit of the asm-generic direction, but the middle one of the 3 bit one
+        let read = if super::ioc::DIRECTION_SHIFT == 30 { 0x80000000 } else { 0x40000000 };
+        assert_eq!(HIDIOCGRDESCSIZE, read | 0x00044801);
+        assert_eq!(HIDIOCGRDESC, read | 0x10044802);
+        assert_eq!(HIDIOCGRAWINFO, read | 0x00084803);
+        // Reading and writing sets the top two bits of both
+        assert_eq!(hidiocsfeature(2), 0xC0024806);
+        assert_eq!(hidiocgfeature(2), 0xC0024807);
+    }
+
+    #[test]
+    fn descriptor_and_reports() {
+        let mut hidraw = Hidraw::new(FakeHidraw::default());
+
+        let info = hidraw.device_info().unwrap();
+        assert_eq!((info.bus, info.vendor_id, info.product_id), (Bus::Usb, 0x0B0E, 0x2456));
+
+        let bytes = hidraw.report_descriptor().unwrap();
+        assert_eq!(bytes, DESCRIPTOR.to_vec());
+        let report = descriptor::try_get_descriptor_report(&bytes).unwrap();
+        let layouts = get_report_layouts(&report);
+
+        let input = hidraw.read_input(&layouts).unwrap();
+        assert_eq!(input.get_value(Usage::new(0x0B, 0x20)), Some(1));
+
+        let feature = find_report_layout(&layouts, Some(2), ReportKind::Feature).unwrap();
+        hidraw.set_feature(feature, &[(Usage::new(0x0B, 0x9E), 1)]).unwrap();
+        assert_eq!(hidraw.get_feature_report(2, 2).unwrap(), vec![0x02, 0x01]);
+        assert_eq!(hidraw.get_feature(feature).unwrap().get_value(Usage::new(0x0B, 0x9E)), Some(1));
+    }
+}
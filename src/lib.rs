@@ -1,105 +1,318 @@
 use core::time;
 use std::{fmt, env};
 
-use rusb::{Device, DeviceHandle, GlobalContext};
+use rusb::{Device, DeviceHandle, GlobalContext, UsbContext};
+
+use hid::class_descriptor::{find_hid_class_descriptor, ClassDescriptorError, HidClassDescriptor};
 
 pub mod hid;
+#[cfg(target_os = "linux")]
+pub mod hidraw;
+pub mod sysfs;
 
 const HID_CLASS_CODE: u8 = 3;
+// The largest report descriptor requested when the HID class descriptor doesn't give its length
+const MAX_REPORT_DESCRIPTOR_LENGTH: usize = 4096;
 
+/// Error returned by the USB functions.
 #[derive(Debug)]
-pub struct Error(String);
+#[non_exhaustive]
+pub enum Error {
+    /// The device has no HID interface.
+    NoHidInterface,
+    /// The interface doesn't exist or is not a HID interface.
+    NotHidInterface(u8),
+    /// The HID interface has no HID class descriptor.
+    NoClassDescriptor(u8),
+    /// The HID class descriptor is malformed.
+    ClassDescriptor(ClassDescriptorError),
+    /// The device can't be opened or claimed without permission, as when a udev rule is missing.
+    Access,
+    /// The interface is claimed by another program or driver.
+    Busy,
+    /// The device didn't answer in time.
+    Timeout,
+    /// The device was unplugged.
+    NoDevice,
+    /// The device returned fewer report descriptor bytes than the HID class descriptor gives.
+    ShortDescriptor { expected: usize, got: usize },
+    /// The configuration descriptor can't be read.
+    ConfigDescriptor(rusb::Error),
+    /// Any other libusb error.
+    Usb(rusb::Error),
+}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+        match self {
+            Error::NoHidInterface => write!(f, "HID interface not found"),
+            Error::NotHidInterface(interface_number) => write!(f, "HID interface {} not found", interface_number),
+            Error::NoClassDescriptor(interface_number) => {
+                write!(f, "HID class descriptor of interface {} not found", interface_number)
+            },
+            Error::ClassDescriptor(error) => write!(f, "Invalid HID class descriptor: {}", error),
+            Error::Access => write!(f, "Access denied, the device may need a udev rule"),
+            Error::Busy => write!(f, "The interface is busy"),
+            Error::Timeout => write!(f, "The device timed out"),
+            Error::NoDevice => write!(f, "The device was disconnected"),
+            Error::ShortDescriptor { expected, got } => write!(
+                f,
+                "The report descriptor is {} bytes, but the HID class descriptor says {}",
+                got, expected
+            ),
+            Error::ConfigDescriptor(error) => write!(f, "Can't read the configuration descriptor: {}", error),
+            Error::Usb(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ClassDescriptor(error) => Some(error),
+            Error::ConfigDescriptor(error) | Error::Usb(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<rusb::Error> for Error {
+    fn from(error: rusb::Error) -> Self {
+        match error {
+            rusb::Error::Access => Error::Access,
+            rusb::Error::Busy => Error::Busy,
+            rusb::Error::Timeout => Error::Timeout,
+            rusb::Error::NoDevice => Error::NoDevice,
+            _ => Error::Usb(error),
+        }
+    }
+}
+
+impl From<ClassDescriptorError> for Error {
+    fn from(error: ClassDescriptorError) -> Self {
+        Error::ClassDescriptor(error)
+    }
+}
+
+/// Finds a device by its vendor ID and product ID, in the global libusb context.
+pub fn find_device(vendor_id: u16, product_id: u16) -> Result<Option<Device<GlobalContext>>, Error> {
+    find_context_device(&GlobalContext::default(), vendor_id, product_id)
+}
+
+/// Finds a device by its vendor ID and product ID, in a libusb context of the application.
+pub fn find_context_device<T: UsbContext>(context: &T, vendor_id: u16, product_id: u16) -> Result<Option<Device<T>>, Error> {
+    for device in context.devices()?.iter() {
+        let device_desc = device.device_descriptor()?;
+        if device_desc.vendor_id() == vendor_id && device_desc.product_id() == product_id {
+            return Ok(Some(device));
+        }
     }
+
+    Ok(None)
 }
 
-impl std::error::Error for Error {}
+/// Reads the report descriptor of the first HID interface of the device.
+pub fn get_hid_descriptor_bytes<T: UsbContext>(device: &Device<T>) -> Result<Vec<u8>, Error> {
+    let interface = get_hid_interfaces(device)?
+        .into_iter()
+        .next()
+        .ok_or(Error::NoHidInterface)?;
+    let mut device_handle = get_device_handle(device)?;
+
+    read_interface_hid_descriptor(&mut device_handle, &interface)
+}
 
-pub fn get_hid_descriptor_bytes(device: &Device<rusb::GlobalContext>) -> Result<Vec<u8>, Error> {
-    let interface_number = get_hid_interface_number(device)?;
+/// Reads the report descriptor of the given HID interface of the device.
+pub fn get_interface_hid_descriptor_bytes<T: UsbContext>(
+    device: &Device<T>,
+    interface_number: u8,
+) -> Result<Vec<u8>, Error> {
+    let interface = find_hid_interface(device, interface_number)?;
     let mut device_handle = get_device_handle(device)?;
 
-    claim_interface(&mut device_handle, interface_number)?;
+    read_interface_hid_descriptor(&mut device_handle, &interface)
+}
+
+/// Reads the report descriptors of every HID interface of the device, as
+/// `(interface number, alternate setting, report descriptor)`.
+///
+/// Composite devices, such as headsets with a telephony and a vendor interface, have several.
+/// Only the first HID alternate setting of each interface is read.
+pub fn get_all_hid_descriptors<T: UsbContext>(device: &Device<T>) -> Result<Vec<(u8, u8, Vec<u8>)>, Error> {
+    let interfaces = get_hid_interfaces(device)?;
+    let mut device_handle = get_device_handle(device)?;
+
+    let mut descriptors = vec![];
+    for interface in interfaces {
+        let hid_descriptor_bytes = read_interface_hid_descriptor(&mut device_handle, &interface)?;
+        descriptors.push((interface.interface_number, interface.alternate_setting, hid_descriptor_bytes));
+    }
+
+    Ok(descriptors)
+}
+
+/// Gets the HID class descriptor of the given HID interface, from the configuration descriptor.
+pub fn get_hid_class_descriptor<T: UsbContext>(
+    device: &Device<T>,
+    interface_number: u8,
+) -> Result<HidClassDescriptor, Error> {
+    match find_hid_interface(device, interface_number)?.class_descriptor {
+        Some(class_descriptor) => Ok(class_descriptor?),
+        None => Err(Error::NoClassDescriptor(interface_number)),
+    }
+}
+
+// A HID interface, with its first HID alternate setting
+struct HidInterface {
+    interface_number: u8,
+    alternate_setting: u8,
+    // A malformed class descriptor only fails reading this interface
+    class_descriptor: Option<Result<HidClassDescriptor, ClassDescriptorError>>,
+}
+
+fn read_interface_hid_descriptor<T: UsbContext>(
+    device_handle: &mut DeviceHandle<T>,
+    interface: &HidInterface,
+) -> Result<Vec<u8>, Error> {
+    let report_descriptor_length = match &interface.class_descriptor {
+        Some(Ok(class_descriptor)) => class_descriptor.report_descriptor_length(),
+        Some(Err(error)) => return Err(Error::ClassDescriptor(*error)),
+        None => None,
+    };
+
+    let claimed_interface = ClaimedInterface::new(device_handle, interface.interface_number)?;
 
-    let hid_desciprtor_bytes = read_hid_descriptor(&device_handle, interface_number)?;
+    let hid_desciprtor_bytes = read_hid_descriptor(
+        claimed_interface.device_handle(),
+        claimed_interface.interface_number(),
+        report_descriptor_length,
+    )?;
 
-    release_interface(&mut device_handle, interface_number)?;
+    claimed_interface.release()?;
 
     Ok(hid_desciprtor_bytes)
 }
 
-fn get_hid_interface_number(device: &Device<rusb::GlobalContext>) -> Result<u8, Error> {
-    // TODO: Handle error
-    let config = device.config_descriptor(0).unwrap();
+fn find_hid_interface<T: UsbContext>(device: &Device<T>, interface_number: u8) -> Result<HidInterface, Error> {
+    let interface = get_hid_interfaces(device)?
+        .into_iter()
+        .find(|interface| interface.interface_number == interface_number);
 
+    interface.ok_or(Error::NotHidInterface(interface_number))
+}
+
+fn get_hid_interfaces<T: UsbContext>(device: &Device<T>) -> Result<Vec<HidInterface>, Error> {
+    let config = device.config_descriptor(0).map_err(Error::ConfigDescriptor)?;
+
+    let mut interfaces = vec![];
     for interface in config.interfaces() {
-        for interface_desc in interface.descriptors() {
-            if interface_desc.class_code() == HID_CLASS_CODE {
-                return Ok(interface_desc.interface_number());
-            }
+        let hid_interface_desc = interface.descriptors().find(|interface_desc| interface_desc.class_code() == HID_CLASS_CODE);
+        if let Some(interface_desc) = hid_interface_desc {
+            let class_descriptor = find_hid_class_descriptor(interface_desc.extra());
+
+            interfaces.push(HidInterface {
+                interface_number: interface_desc.interface_number(),
+                alternate_setting: interface_desc.setting_number(),
+                class_descriptor,
+            });
         }
     }
 
-    Err(Error("HID interface not found".into()))
+    Ok(interfaces)
 }
 
-fn get_device_handle(device: &Device<rusb::GlobalContext>) -> Result<DeviceHandle<GlobalContext>, Error> {
-    let device_handle_res = device.open();
-    match device_handle_res {
-        Ok(device_handle) => Ok(device_handle),
-        Err(error) => return Err(Error(error.to_string())),
-    }
+fn get_device_handle<T: UsbContext>(device: &Device<T>) -> Result<DeviceHandle<T>, Error> {
+    Ok(device.open()?)
 }
 
-fn claim_interface(device_handle: &mut DeviceHandle<rusb::GlobalContext>, interface_number: u8) -> Result<(), Error> {
-    match env::consts::OS {
-        "linux" => {
-            let detach_res = device_handle.set_auto_detach_kernel_driver(true);
-            
-            match detach_res {
-                Ok(_) => {},
-                Err(error) => {
-                    match error {
-                        rusb::Error::NotSupported => { /* No problem, detach not needed if not supported */ },
-                        _ => return Err(Error(error.to_string())),
-                    }
+/// A claimed interface, released when dropped.
+///
+/// On Linux, the kernel driver of the interface is detached while it's claimed, and reattached
+/// when it's released, even when a read in between fails.
+pub struct ClaimedInterface<'a, T: UsbContext = GlobalContext> {
+    device_handle: &'a mut DeviceHandle<T>,
+    interface_number: u8,
+    claimed: bool,
+    kernel_driver_detached: bool,
+}
+
+impl<'a, T: UsbContext> ClaimedInterface<'a, T> {
+    /// Detaches the kernel driver of the interface, if one is attached, and claims the interface.
+    pub fn new(device_handle: &'a mut DeviceHandle<T>, interface_number: u8) -> Result<Self, Error> {
+        let mut claimed_interface = ClaimedInterface {
+            device_handle,
+            interface_number,
+            claimed: false,
+            kernel_driver_detached: false,
+        };
+
+        if env::consts::OS == "linux" {
+            match claimed_interface.device_handle.kernel_driver_active(interface_number) {
+                Ok(true) => {
+                    claimed_interface.device_handle.detach_kernel_driver(interface_number)?;
+                    claimed_interface.kernel_driver_detached = true;
                 },
+                Ok(false) => {},
+                Err(rusb::Error::NotSupported) => { /* No problem, detach not needed if not supported */ },
+                Err(error) => return Err(error.into()),
             }
-            
-            let claim_res = device_handle.claim_interface(interface_number);
-            match claim_res {
-                Ok(_) => Ok(()),
-                Err(error) => Err(Error(error.to_string())),
-            }
-        },
-        _ => Ok(())
+
+            // Dropping the guard on error reattaches the kernel driver
+            claimed_interface.device_handle.claim_interface(interface_number)?;
+            claimed_interface.claimed = true;
+        }
+
+        Ok(claimed_interface)
+    }
+
+    pub fn device_handle(&self) -> &DeviceHandle<T> {
+        self.device_handle
+    }
+
+    pub fn interface_number(&self) -> u8 {
+        self.interface_number
+    }
+
+    /// Releases the interface and reattaches the kernel driver, returning the first error.
+    ///
+    /// Dropping the guard does the same, but ignores the errors.
+    pub fn release(mut self) -> Result<(), Error> {
+        self.release_interface()
+    }
+
+    fn release_interface(&mut self) -> Result<(), Error> {
+        let mut result = Ok(());
+
+        if self.claimed {
+            self.claimed = false;
+            result = self.device_handle.release_interface(self.interface_number).map_err(Error::from);
+        }
+        if self.kernel_driver_detached {
+            self.kernel_driver_detached = false;
+            let attach_result = self.device_handle.attach_kernel_driver(self.interface_number);
+            result = result.and(attach_result.map_err(Error::from));
+        }
+
+        result
     }
 }
 
-fn release_interface(device_handle: &mut DeviceHandle<rusb::GlobalContext>, interface_number: u8) -> Result<(), Error> {
-    match env::consts::OS {
-        "linux" => {
-            match device_handle.release_interface(interface_number) {
-                Ok(_) => Ok(()),
-                Err(error) => Err(Error(error.to_string())),
-            }
-        },
-        _ => Ok(())
+impl<T: UsbContext> Drop for ClaimedInterface<'_, T> {
+    fn drop(&mut self) {
+        let _ = self.release_interface();
     }
 }
 
-fn read_hid_descriptor(
-    device_handle: &rusb::DeviceHandle<rusb::GlobalContext>,
+fn read_hid_descriptor<T: UsbContext>(
+    device_handle: &DeviceHandle<T>,
     interface_number: u8,
+    report_descriptor_length: Option<u16>,
 ) -> Result<Vec<u8>, Error> {
     let request_type = rusb::request_type(rusb::Direction::In, rusb::RequestType::Standard, rusb::Recipient::Interface);
     let request = rusb::constants::LIBUSB_REQUEST_GET_DESCRIPTOR;
     let request_value = (rusb::constants::LIBUSB_DT_REPORT as u16) << 8;
     let request_index: u16 = interface_number.into();
-    let mut output_buffer = [0u8; 4096];
+    let mut output_buffer = vec![0u8; report_descriptor_length.map_or(MAX_REPORT_DESCRIPTOR_LENGTH, usize::from)];
     let timeout = time::Duration::from_secs(1);
 
     let c = device_handle.read_control(
@@ -112,16 +325,31 @@ fn read_hid_descriptor(
     );
 
     match c {
+        Ok(result_size) if report_descriptor_length.is_some_and(|length| result_size != length as usize) => {
+            Err(Error::ShortDescriptor {
+                expected: output_buffer.len(),
+                got: result_size,
+            })
+        },
         Ok(result_size) => Ok(output_buffer[0..result_size].to_vec()),
-        Err(error) => Err(Error(error.to_string())),
+        Err(error) => Err(error.into()),
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::error::Error as _;
+
+    use super::Error;
+
     #[test]
-    fn it_works() {
-        let result = 2 + 2;
-        assert_eq!(result, 4);
+    fn usb_errors() {
+        assert!(matches!(Error::from(rusb::Error::Access), Error::Access));
+        assert!(matches!(Error::from(rusb::Error::NoDevice), Error::NoDevice));
+        assert!(Error::Busy.source().is_none());
+
+        let error = Error::from(rusb::Error::Pipe);
+        assert!(matches!(error, Error::Usb(rusb::Error::Pipe)));
+        assert!(error.source().is_some());
     }
 }
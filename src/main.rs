@@ -0,0 +1,28 @@
+use std::{env, fs, process};
+
+use usb_hid_descriptor_parser::hid::{codegen, descriptor};
+
+const USAGE: &str = "Usage: usb_hid_descriptor_parser codegen <descriptor file>";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.iter().map(String::as_str).collect::<Vec<&str>>().as_slice() {
+        ["codegen", path] => run_codegen(path),
+        _ => Err(USAGE.to_string()),
+    };
+
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        process::exit(1);
+    }
+}
+
+// Prints the report structs of a binary report descriptor
+fn run_codegen(path: &str) -> Result<(), String> {
+    let bytes = fs::read(path).map_err(|error| format!("Can't read {}: {}", path, error))?;
+    let report = descriptor::try_get_descriptor_report(&bytes).map_err(|error| error.to_string())?;
+
+    print!("{}", codegen::generate_rust(&report));
+    Ok(())
+}
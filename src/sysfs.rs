@@ -0,0 +1,220 @@
+//! Reads report descriptors from Linux sysfs, without claiming the interface or detaching the
+//! kernel driver. This works for USB, Bluetooth and I2C HID devices alike, and doesn't need root.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const BUS_USB: u16 = 0x03;
+const BUS_BLUETOOTH: u16 = 0x05;
+const BUS_I2C: u16 = 0x18;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bus {
+    Usb,
+    Bluetooth,
+    I2c,
+    /// Another bus type, as numbered in `linux/input.h`.
+    Other(u16),
+}
+
+impl Bus {
+    pub(crate) fn from_value(value: u16) -> Self {
+        match value {
+            BUS_USB => Bus::Usb,
+            BUS_BLUETOOTH => Bus::Bluetooth,
+            BUS_I2C => Bus::I2c,
+            _ => Bus::Other(value),
+        }
+    }
+}
+
+/// A HID device as listed in `/sys/bus/hid/devices`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HidDevice {
+    /// The device directory, as in `/sys/bus/hid/devices/0003:0B0E:2456.0007`.
+    pub path: PathBuf,
+    pub bus: Bus,
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub name: Option<String>,
+    /// The hidraw node of the device, as in `hidraw3`, when it has one.
+    pub hidraw: Option<String>,
+}
+
+/// The sysfs tree, normally mounted at `/sys`.
+#[derive(Debug, Clone)]
+pub struct Sysfs {
+    root: PathBuf,
+}
+
+impl Default for Sysfs {
+    fn default() -> Self {
+        Sysfs::with_root("/sys")
+    }
+}
+
+impl Sysfs {
+    pub fn new() -> Self {
+        Sysfs::default()
+    }
+
+    /// A sysfs tree mounted somewhere else, or a copy of one.
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Sysfs { root: root.into() }
+    }
+
+    /// Lists the HID devices, ordered by their directory name.
+    ///
+    /// Devices without a valid `HID_ID` in their `uevent` are skipped.
+    pub fn list_devices(&self) -> io::Result<Vec<HidDevice>> {
+        let mut paths = vec![];
+        for entry in fs::read_dir(self.root.join("bus/hid/devices"))? {
+            paths.push(entry?.path());
+        }
+        paths.sort();
+
+        let mut devices = vec![];
+        for path in &paths {
+            match read_device(path) {
+                Ok(device) => devices.push(device),
+                Err(error) if error.kind() == io::ErrorKind::InvalidData => {},
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(devices)
+    }
+
+    /// Reads the report descriptor of a device.
+    pub fn read_report_descriptor(&self, device: &HidDevice) -> io::Result<Vec<u8>> {
+        fs::read(device.path.join("report_descriptor"))
+    }
+
+    /// Reads the report descriptor of a hidraw node, as in `hidraw3`.
+    pub fn read_hidraw_report_descriptor(&self, hidraw: &str) -> io::Result<Vec<u8>> {
+        fs::read(self.root.join("class/hidraw").join(hidraw).join("device/report_descriptor"))
+    }
+}
+
+fn read_device(path: &Path) -> io::Result<HidDevice> {
+    let uevent = fs::read_to_string(path.join("uevent"))?;
+
+    let mut hid_id = None;
+    let mut name = None;
+    for line in uevent.lines() {
+        match line.split_once('=') {
+            Some(("HID_ID", value)) => hid_id = Some(value),
+            Some(("HID_NAME", value)) => name = Some(value.to_string()),
+            _ => {},
+        }
+    }
+
+    // HID_ID is the bus, vendor ID and product ID in hex, as in `0003:00000B0E:00002456`
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, format!("Invalid HID_ID in {}", path.display()));
+    let fields: Vec<u32> = hid_id
+        .ok_or_else(invalid)?
+        .split(':')
+        .map(|field| u32::from_str_radix(field, 16))
+        .collect::<Result<Vec<u32>, _>>()
+        .map_err(|_| invalid())?;
+    let (bus, vendor_id, product_id) = match fields.as_slice() {
+        [bus, vendor_id, product_id] => (*bus as u16, *vendor_id as u16, *product_id as u16),
+        _ => return Err(invalid()),
+    };
+
+    Ok(HidDevice {
+        path: path.to_path_buf(),
+        bus: Bus::from_value(bus),
+        vendor_id,
+        product_id,
+        name,
+        hidraw: read_hidraw_name(path),
+    })
+}
+
+fn read_hidraw_name(path: &Path) -> Option<String> {
+    let mut names: Vec<String> = fs::read_dir(path.join("hidraw"))
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names.into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use super::{Bus, Sysfs};
+
+    fn fake_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("usb_hid_descriptor_parser_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        root
+    }
+
+    fn add_device(root: &Path, name: &str, uevent: &str, descriptor: &[u8], hidraw: Option<&str>) {
+        let path = root.join("bus/hid/devices").join(name);
+        fs::create_dir_all(&path).unwrap();
+        fs::write(path.join("uevent"), uevent).unwrap();
+        fs::write(path.join("report_descriptor"), descriptor).unwrap();
+
+        if let Some(hidraw) = hidraw {
+            fs::create_dir_all(path.join("hidraw").join(hidraw)).unwrap();
+            let class = root.join("class/hidraw").join(hidraw).join("device");
+            fs::create_dir_all(&class).unwrap();
+            fs::write(class.join("report_descriptor"), descriptor).unwrap();
+        }
+    }
+
+    #[test]
+    fn list_and_read_devices() {
+        let root = fake_root("list");
+        add_device(
+            &root,
+            "0003:0B0E:2456.0007",
+            "DRIVER=hid-generic\nHID_ID=0003:00000B0E:00002456\nHID_NAME=GN Netcom A/S Jabra EVOLVE 65\n",
+            &[0x05, 0x0B, 0x09, 0x05],
+            Some("hidraw3"),
+        );
+        add_device(
+            &root,
+            "0005:0B0E:245E.0008",
+            "HID_ID=0005:00000B0E:0000245E\nHID_NAME=Jabra Evolve 75\n",
+            &[0x05, 0x0C],
+            None,
+        );
+
+        let sysfs = Sysfs::with_root(&root);
+        let devices = sysfs.list_devices().unwrap();
+        assert_eq!(devices.len(), 2);
+
+        assert_eq!(devices[0].bus, Bus::Usb);
+        assert_eq!((devices[0].vendor_id, devices[0].product_id), (0x0B0E, 0x2456));
+        assert_eq!(devices[0].name.as_deref(), Some("GN Netcom A/S Jabra EVOLVE 65"));
+        assert_eq!(devices[0].hidraw.as_deref(), Some("hidraw3"));
+        assert_eq!(devices[1].bus, Bus::Bluetooth);
+        assert_eq!(devices[1].hidraw, None);
+
+        assert_eq!(sysfs.read_report_descriptor(&devices[1]).unwrap(), vec![0x05, 0x0C]);
+        assert_eq!(sysfs.read_hidraw_report_descriptor("hidraw3").unwrap(), vec![0x05, 0x0B, 0x09, 0x05]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn invalid_uevent() {
+        let root = fake_root("invalid");
+        add_device(&root, "0003:0B0E:2456.0007", "HID_ID=0003:00000B0E\n", &[], None);
+        add_device(&root, "0003:0B0E:2456.0008", "HID_NAME=Jabra Evolve 75\n", &[], None);
+        add_device(&root, "0005:0B0E:245E.0009", "HID_ID=0005:00000B0E:0000245E\n", &[], None);
+
+        let devices = Sysfs::with_root(&root).list_devices().unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!((devices[0].vendor_id, devices[0].product_id), (0x0B0E, 0x245E));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,54 @@
+use usb_hid_descriptor_parser::hid::codegen::generate_rust;
+use usb_hid_descriptor_parser::hid::descriptor;
+
+// Generated from HEADSET, checked in so that the generated code is compiled
+#[allow(dead_code)]
+mod headset {
+    include!("codegen/headset.rs");
+}
+
+use headset::{InputReport2, OutputReport2, ReportError};
+
+const HEADSET: [u8; 43] = [
+    0x05, 0x0B,       // Usage Page (Telephony Page)
+    0x09, 0x05,       // Usage (Headset)
+    0xA1, 0x01,       // Collection (Application)
+    0x85, 0x02,       // Report ID (2)
+    0x15, 0x00,       // Logical Minimum (0)
+    0x25, 0x01,       // Logical Maximum (1)
+    0x09, 0x20,       // Usage (Hook Switch)
+    0x09, 0x2F,       // Usage (Phone Mute)
+    0x75, 0x01,       // Report Size (1)
+    0x95, 0x02,       // Report Count (2)
+    0x81, 0x02,       // Input (Data, Variable, Absolute)
+    0x95, 0x06,       // Report Count (6)
+    0x81, 0x01,       // Input (Constant, Array, Absolute)
+    0x05, 0x0C,       // Usage Page (Consumer Page)
+    0x09, 0xE0,       // Usage (Volume)
+    0x16, 0x00, 0xFF, // Logical Minimum (-256)
+    0x26, 0xFF, 0x00, // Logical Maximum (255)
+    0x75, 0x10,       // Report Size (16)
+    0x95, 0x01,       // Report Count (1)
+    0x91, 0x06,       // Output (Data, Variable, Relative)
+    0xC0,             // End Collection
+];
+
+#[test]
+fn fixture_is_generated() {
+    let report = descriptor::try_get_descriptor_report(&HEADSET).unwrap();
+
+    assert_eq!(generate_rust(&report), include_str!("codegen/headset.rs"));
+}
+
+#[test]
+fn generated_reports() {
+    let input = InputReport2::from_bytes(&[0x02, 0b10]).unwrap();
+    assert_eq!(input, InputReport2 { hook_switch: false, phone_mute: true });
+    assert_eq!(input.to_bytes(), vec![0x02, 0b10]);
+    assert_eq!(InputReport2::from_bytes(&[0x03, 0x00]), Err(ReportError::WrongReportId(0x03)));
+    assert_eq!(InputReport2::from_bytes(&[0x02]), Err(ReportError::TooShort { expected: 2, got: 1 }));
+
+    let output = OutputReport2 { volume: -256 };
+    assert_eq!(output.to_bytes(), vec![0x02, 0x00, 0xFF]);
+    assert_eq!(OutputReport2::from_bytes(&[0x02, 0x00, 0xFF]), Ok(output));
+}
@@ -0,0 +1,106 @@
+// Generated by usb_hid_descriptor_parser, do not edit.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportError {
+    /// The report is shorter than its layout.
+    TooShort { expected: usize, got: usize },
+    /// The Report ID byte doesn't match the report.
+    WrongReportId(u8),
+}
+
+fn get_bits(data: &[u8], bit_offset: u32, bit_count: u32) -> u64 {
+    let mut value = 0u64;
+    for bit in 0..bit_count {
+        let position = (bit_offset + bit) as usize;
+        if data[position / 8] & (1 << (position % 8)) != 0 {
+            value |= 1 << bit;
+        }
+    }
+    value
+}
+
+fn set_bits(data: &mut [u8], bit_offset: u32, bit_count: u32, value: u64) {
+    for bit in 0..bit_count {
+        let position = (bit_offset + bit) as usize;
+        if value & (1 << bit) != 0 {
+            data[position / 8] |= 1 << (position % 8);
+        }
+    }
+}
+
+fn sign_extend(value: u64, bit_count: u32) -> i64 {
+    let shift = 64 - bit_count;
+    ((value << shift) as i64) >> shift
+}
+
+/// The input report with Report ID 2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputReport2 {
+    /// Hook Switch (0x000B:0x0020)
+    pub hook_switch: bool,
+    /// Phone Mute (0x000B:0x002F)
+    pub phone_mute: bool,
+}
+
+impl InputReport2 {
+    pub const REPORT_ID: u8 = 2;
+    pub const LENGTH: usize = 2;
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReportError> {
+        if bytes.len() < Self::LENGTH {
+            return Err(ReportError::TooShort { expected: Self::LENGTH, got: bytes.len() });
+        }
+        if bytes[0] != Self::REPORT_ID {
+            return Err(ReportError::WrongReportId(bytes[0]));
+        }
+        let data = &bytes[1..];
+
+        Ok(InputReport2 {
+            hook_switch: get_bits(data, 0, 1) != 0,
+            phone_mute: get_bits(data, 1, 1) != 0,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0; Self::LENGTH];
+        bytes[0] = Self::REPORT_ID;
+        let data = &mut bytes[1..];
+        set_bits(data, 0, 1, self.hook_switch as u64);
+        set_bits(data, 1, 1, self.phone_mute as u64);
+        bytes
+    }
+}
+
+/// The output report with Report ID 2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputReport2 {
+    /// Volume (0x000C:0x00E0)
+    pub volume: i16,
+}
+
+impl OutputReport2 {
+    pub const REPORT_ID: u8 = 2;
+    pub const LENGTH: usize = 3;
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReportError> {
+        if bytes.len() < Self::LENGTH {
+            return Err(ReportError::TooShort { expected: Self::LENGTH, got: bytes.len() });
+        }
+        if bytes[0] != Self::REPORT_ID {
+            return Err(ReportError::WrongReportId(bytes[0]));
+        }
+        let data = &bytes[1..];
+
+        Ok(OutputReport2 {
+            volume: sign_extend(get_bits(data, 0, 16), 16) as i16,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0; Self::LENGTH];
+        bytes[0] = Self::REPORT_ID;
+        let data = &mut bytes[1..];
+        set_bits(data, 0, 16, self.volume as u64);
+        bytes
+    }
+}